@@ -0,0 +1,91 @@
+use std::fmt;
+use std::io;
+
+use inkverify_core::{GridError, ProofError};
+
+/// Exit code for a run stopped by Ctrl-C (128 + SIGINT, as shells report it).
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Everything that can stop the CLI. `main` prints it and exits with `exit_code`.
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad command line (unknown flag, missing value, impossible sizes).
+    /// Printed together with the usage text.
+    Usage(String),
+    /// A file (or stdout) could not be read or written.
+    Io { context: String, source: io::Error },
+    /// A proof string or `.ivp` artifact could not be parsed.
+    Proof(ProofError),
+    /// The inputs were understood but refused: weak password, extinct grid,
+    /// invalid proof.
+    Rejected(String),
+    /// Ctrl-C stopped the simulation after `step` of `steps` ticks.
+    Interrupted { step: usize, steps: usize },
+}
+
+impl CliError {
+    /// Adapter for `map_err`: an I/O failure described by `context`,
+    /// e.g. `failed to write image 'proof.ppm'`.
+    pub fn io(context: impl Into<String>) -> impl FnOnce(io::Error) -> CliError {
+        let context = context.into();
+        move |source| CliError::Io { context, source }
+    }
+
+    /// Process exit code: 1 = rejected, 2 = I/O, 3 = bad arguments or input,
+    /// 130 = interrupted.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Interrupted { .. } => INTERRUPTED_EXIT_CODE,
+            CliError::Rejected(_) => 1,
+            CliError::Io { .. } => 2,
+            CliError::Usage(_) | CliError::Proof(_) => 3,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Rejected(message) => f.write_str(message),
+            CliError::Io { context, source } => write!(f, "{}: {}", context, source),
+            CliError::Proof(err) => err.fmt(f),
+            CliError::Interrupted { step, steps } => {
+                write!(f, "interrupted at step {} of {}; no proof or image was written", step, steps)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CliError::Io { source, .. } => Some(source),
+            CliError::Proof(err) => Some(err),
+            CliError::Usage(_) | CliError::Rejected(_) | CliError::Interrupted { .. } => None,
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Usage(message)
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(message: &str) -> Self {
+        CliError::Usage(message.to_string())
+    }
+}
+
+impl From<GridError> for CliError {
+    fn from(err: GridError) -> Self {
+        CliError::Usage(err.to_string())
+    }
+}
+
+impl From<ProofError> for CliError {
+    fn from(err: ProofError) -> Self {
+        CliError::Proof(err)
+    }
+}
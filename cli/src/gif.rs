@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use inkverify_core::Grid;
+
+/// Smallest LZW code size GIF allows; our palette only needs 1 bit.
+const MIN_CODE_SIZE: u8 = 2;
+
+/// LZW codes never grow past 12 bits in GIF.
+const MAX_CODES: u16 = 4096;
+
+/// Delay between frames, in hundredths of a second.
+const FRAME_DELAY: u16 = 10;
+
+/// Streams grids into a looping, two-color animated GIF (GIF89a).
+///
+/// Frames are written as they arrive, so long runs never hold more than one
+/// frame in memory. Every frame must have the dimensions given to `new`, and
+/// `finish` must be called to write the trailer.
+pub struct GifWriter<W: Write> {
+    out: W,
+    width: u16,
+    height: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Writes the header, palette (`dead`, `alive`) and loop extension.
+    pub fn new(mut out: W, width: usize, height: usize, dead: (u8, u8, u8), alive: (u8, u8, u8)) -> io::Result<Self> {
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "GIF frames are limited to 65535 pixels per side");
+        let width = u16::try_from(width).map_err(|_| too_large())?;
+        let height = u16::try_from(height).map_err(|_| too_large())?;
+
+        out.write_all(b"GIF89a")?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        // Global color table present, 2 entries.
+        out.write_all(&[0x80, 0, 0])?;
+        out.write_all(&[dead.0, dead.1, dead.2, alive.0, alive.1, alive.2])?;
+        // NETSCAPE2.0 extension: loop forever.
+        out.write_all(&[0x21, 0xFF, 0x0B])?;
+        out.write_all(b"NETSCAPE2.0")?;
+        out.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        Ok(GifWriter { out, width, height })
+    }
+
+    /// Appends one frame. Non-zero cells use the `alive` color.
+    pub fn frame(&mut self, grid: &Grid<u8>) -> io::Result<()> {
+        assert!(
+            grid.width() == usize::from(self.width) && grid.height() == usize::from(self.height),
+            "Frame dimensions must match the animation"
+        );
+
+        // Graphic control extension: frame delay, no transparency.
+        self.out.write_all(&[0x21, 0xF9, 0x04, 0x00])?;
+        self.out.write_all(&FRAME_DELAY.to_le_bytes())?;
+        self.out.write_all(&[0x00, 0x00])?;
+
+        // Image descriptor: full-canvas frame, no local palette.
+        self.out.write_all(&[0x2C, 0, 0, 0, 0])?;
+        self.out.write_all(&self.width.to_le_bytes())?;
+        self.out.write_all(&self.height.to_le_bytes())?;
+        self.out.write_all(&[0x00, MIN_CODE_SIZE])?;
+
+        // Row by row through `get`, so column-major grids come out right too.
+        let pixels: Vec<u8> = (0..grid.height() as isize)
+            .flat_map(|y| (0..grid.width() as isize).map(move |x| u8::from(grid.get(x, y) != 0)))
+            .collect();
+        for block in lzw_encode(&pixels).chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0x00])
+    }
+
+    /// Writes the trailer and flushes.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&[0x3B])?;
+        self.out.flush()
+    }
+}
+
+/// Variable-width LZW as GIF expects it: codes packed least-significant bit first,
+/// starting with a clear code, resetting the table whenever it fills up.
+fn lzw_encode(pixels: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+
+    let mut bits = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = MIN_CODE_SIZE + 1;
+    bits.write(clear, code_size);
+
+    let mut prefix: Option<u16> = None;
+    for &pixel in pixels {
+        let Some(current) = prefix else {
+            prefix = Some(u16::from(pixel));
+            continue;
+        };
+        if let Some(&code) = table.get(&(current, pixel)) {
+            prefix = Some(code);
+            continue;
+        }
+
+        bits.write(current, code_size);
+        if next_code < MAX_CODES {
+            table.insert((current, pixel), next_code);
+            next_code += 1;
+            if next_code > (1 << code_size) {
+                code_size += 1;
+            }
+        } else {
+            bits.write(clear, code_size);
+            table.clear();
+            next_code = end + 1;
+            code_size = MIN_CODE_SIZE + 1;
+        }
+        prefix = Some(u16::from(pixel));
+    }
+
+    if let Some(current) = prefix {
+        bits.write(current, code_size);
+    }
+    bits.write(end, code_size);
+    bits.finish()
+}
+
+/// Packs codes of varying width into bytes, least-significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    len: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.acc |= u32::from(code) << self.len;
+        self.len += size;
+        while self.len >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use inkverify_core::{generate_grid_from_seed, Layout};
+
+    fn encode(grid: &Grid<u8>) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut gif = GifWriter::new(&mut out, grid.width(), grid.height(), (255, 255, 255), (0, 0, 0)).unwrap();
+        gif.frame(grid).unwrap();
+        gif.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn frames_ignore_the_grid_layout() {
+        let grid = generate_grid_from_seed("alice", "secret", 7, 5);
+        let bytes = encode(&grid);
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert_eq!(bytes.last(), Some(&0x3B));
+        assert_eq!(encode(&grid.into_layout(Layout::ColMajor)), bytes);
+    }
+}
@@ -1,12 +1,61 @@
+mod error;
+mod gif;
+
 use std::env;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::mem;
+use std::path::Path;
 use std::process;
-use std::time::Instant;
-use sha2::{Sha256, Digest};
-use hex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use inkverify_core::{
+    benchmark_vectors, calibrate_steps, encode_ppm_as, encode_ppm_labels, estimate_footprint,
+    estimate_password_bits, estimate_runtime, format_proof, hash_grid, known_vectors,
+    params_for_difficulty, parse_proof, run_simulation, run_simulation_until,
+    verify_artifact_with_limits, engine::MAX_DIFFICULTY_LEVEL, grid::cell_count,
+    try_generate_grid_from_seed, Grid, GridError, HashAlgo, Neighborhood, ProofArtifact, ProofError,
+    ppm::{INK_BLACK, PAPER_WHITE}, AliveTest, ProofParams, RunOutcome, SeedEndian, SimMetrics, StorageKind,
+    VerifyLimits,
+};
+
+use error::{CliError, INTERRUPTED_EXIT_CODE};
+use gif::GifWriter;
+
+/// Output path meaning "write the image to stdout".
+const STDOUT_PATH: &str = "-";
+
+/// Default cells per dimension for `--preview` (small grids are shown whole).
+const PREVIEW_LIMIT: usize = 64;
 
-use inkverify_core::{generate_grid_from_seed, run_simulation, Grid};
+/// Default `--max-steps`: far beyond any real challenge, but it turns a typo
+/// like an extra few zeros into an error instead of a run that never ends.
+const DEFAULT_MAX_STEPS: usize = 1_000_000;
+
+/// Step cap for each `--warmup` run: enough ticks to reach steady-state
+/// caches and clock speed, short enough to add little to the total run time.
+const WARMUP_RUN_STEPS: usize = 32;
+
+/// Default `bench` rounds: enough to smooth out a cold first pass over the
+/// vectors, short enough to run after every engine change.
+const DEFAULT_BENCH_ROUNDS: usize = 5;
+
+/// Set by the Ctrl-C handler; the simulation checks it between ticks.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Prints a progress line for the prove command.
+/// When the image or the `--json` summary goes to stdout, progress goes to
+/// stderr instead so stdout stays clean for piping into other tools.
+macro_rules! status {
+    ($config:expr, $($arg:tt)*) => {
+        if $config.output_file == STDOUT_PATH || $config.json {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
 
 // --- Configuration Struct ---
 struct Config {
@@ -16,107 +65,1045 @@ struct Config {
     height: usize,
     steps: usize,
     output_file: String,
+    target_time: Option<Duration>,
+    min_password_bits: Option<f64>,
+    /// ASCII preview size printed to stderr (`None` = no preview, 0 = full grid).
+    preview_limit: Option<usize>,
+    alive_test: AliveTest,
+    /// Treat a grid that dies out completely as an error instead of a warning.
+    reject_extinct: bool,
+    animation: Option<Animation>,
+    dump: Option<FrameDump>,
+    /// Only estimate the cost of the run (`--dry-run`), then exit.
+    dry_run: bool,
+    /// Challenge/session string mixed into the final hash (empty = plain proof).
+    context: String,
+    /// Print a population / neighbor-count summary after the run (`--stats`).
+    stats: bool,
+    /// Also write the proof as a binary `.ivp` artifact to this path.
+    proof_file: Option<String>,
+    /// Embed the seed grid in the `.ivp` artifact for credential-free audits.
+    embed_seed: bool,
+    /// Also print the proof as a base32 short-form token (`--short`).
+    short: bool,
+    /// Digest for the final hash (`--hash`, SHA-256 by default).
+    hash_algo: HashAlgo,
+    /// Worker threads for the parallel engine (`--threads`, `None` = all cores).
+    threads: Option<usize>,
+    /// Refuse runs longer than this many steps (`--max-steps`).
+    max_steps: usize,
+    /// SHA-256 rounds when deriving the seed (`--iterations`, 1 = a single hash).
+    iterations: u32,
+    /// PRNG outputs discarded before seeding (`--prng-warmup`, 0 = none).
+    prng_warmup: u32,
+    /// Throwaway simulations run before the timed one (`--warmup`, 0 = none).
+    warmup_runs: usize,
+    /// Also write a color-coded connected-component map of the final grid here.
+    components_file: Option<String>,
+    /// Print a one-line JSON summary of the proof to stdout (`--json`).
+    json: bool,
+    /// Include the population every this many ticks in the JSON summary
+    /// (`--with-timeline`, `--timeline-every`; `None` = no timeline).
+    timeline_every: Option<usize>,
+}
+
+/// Settings for `--animate`: which generations become GIF frames, and how big.
+struct Animation {
+    output_file: String,
+    /// Record the seed, then every `frame_every`-th generation.
+    frame_every: usize,
+    /// Stop recording (but keep simulating) after this many frames.
+    max_frames: Option<usize>,
+    /// Downsampling factor applied to each frame (`Grid::downsample`).
+    scale: usize,
+}
+
+/// Settings for `--dump-dir`: every `every`-th generation, starting with the
+/// seed, is written to `dir` as `frame_<step>.ppm`.
+struct FrameDump {
+    dir: String,
+    every: usize,
+}
+
+/// Settings for `batch` mode (many credentials, one CSV in, one CSV out).
+struct BatchConfig {
+    input_file: String,
+    output_file: String,
+    width: usize,
+    height: usize,
+    steps: usize,
 }
 
 fn main() {
-    // 1. Parse Arguments
-    let config = parse_args().unwrap_or_else(|err| {
+    if let Err(err) = run() {
         eprintln!("Error: {}", err);
-        print_usage();
-        process::exit(1);
-    });
+        if let CliError::Usage(_) = err {
+            print_usage();
+        }
+        process::exit(err.exit_code());
+    }
+}
+
+fn run() -> Result<(), CliError> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().is_some_and(|a| a == "batch") {
+        let config = parse_batch_args(&args[1..])?;
+        return run_batch(&config);
+    }
 
-    println!("--- InkVerify Protocol ---");
-    println!("[*] User: {}", config.username);
-    println!("[*] Grid: {}x{}", config.width, config.height);
-    println!("[*] Steps: {}", config.steps);
+    if args.first().is_some_and(|a| a == "verify") {
+        return run_verify(&args[1..]);
+    }
+
+    if args.first().is_some_and(|a| a == "selftest") {
+        return run_selftest();
+    }
+
+    if args.first().is_some_and(|a| a == "bench") {
+        return run_bench(&args[1..]);
+    }
+
+    if args.first().is_some_and(|a| a == "decode") {
+        return run_decode(&args[1..]);
+    }
+
+    if args.first().is_some_and(|a| a == "audit") {
+        return run_audit(&args[1..]);
+    }
+
+    // 1. Parse Arguments
+    let config = parse_args(&args)?;
+    let threads = config.threads;
+    with_thread_pool(threads, move || prove(config))?
+}
+
+/// Runs `work` on a local pool of `threads` workers (all cores when `None`), so
+/// the parallel engine never uses more than asked. The pool size only changes
+/// speed: proofs are identical for any thread count.
+#[cfg(feature = "parallel")]
+fn with_thread_pool<R: Send>(threads: Option<usize>, work: impl FnOnce() -> R + Send) -> Result<R, CliError> {
+    let mut builder = rayon::ThreadPoolBuilder::new();
+    if let Some(threads) = threads {
+        builder = builder.num_threads(threads);
+    }
+    let pool = builder
+        .build()
+        .map_err(|err| CliError::Rejected(format!("cannot start the worker thread pool: {}", err)))?;
+    Ok(pool.install(work))
+}
+
+/// Serial build: there is no pool to size, everything runs on this thread.
+#[cfg(not(feature = "parallel"))]
+fn with_thread_pool<R>(_threads: Option<usize>, work: impl FnOnce() -> R) -> Result<R, CliError> {
+    Ok(work())
+}
+
+/// The default command: prove one credential pair and write the visual proof.
+fn prove(mut config: Config) -> Result<(), CliError> {
+
+    status!(config, "--- InkVerify Protocol ---");
+    status!(config, "[*] User: {}", config.username);
+
+    // Refuse to build a proof on a trivially guessable seed.
+    if let Some(min_bits) = config.min_password_bits {
+        let bits = estimate_password_bits(&config.password);
+        if bits < min_bits {
+            return Err(CliError::Rejected(format!(
+                "password entropy is ~{:.1} bits, below the required {:.1} bits.\n       \
+                 Use a longer password or mix character classes.",
+                bits, min_bits
+            )));
+        }
+        status!(config, "[*] Password entropy: ~{:.1} bits", bits);
+    }
+    status!(config, "[*] Grid: {}x{}", config.width, config.height);
+
+    // Optional: pick the step count from a wall-clock budget instead.
+    if let Some(target) = config.target_time {
+        status!(config, "[*] Calibrating steps for a {:.2?} target...", target);
+        config.steps = calibrate_steps(config.width, config.height, target);
+    }
+    status!(config, "[*] Steps: {}", config.steps);
+    if config.steps > config.max_steps {
+        return Err(CliError::Usage(format!(
+            "{} steps exceeds the --max-steps cap of {} (raise it to allow longer runs).",
+            config.steps, config.max_steps
+        )));
+    }
+
+    // Optional: report what the run would cost, then stop before doing it.
+    if config.dry_run {
+        print_dry_run(&config);
+        return Ok(());
+    }
 
     // 2. Initialize (The "Seed")
     let start_time = Instant::now();
-    println!("[1] Generating Initial Seed...");
-    let initial_grid = generate_grid_from_seed(
-        &config.username, 
-        &config.password, 
-        config.width, 
-        config.height
-    );
+    status!(config, "[1] Generating Initial Seed...");
+    if config.iterations != 1 {
+        status!(config, "[*] Seed iterations: {}", config.iterations);
+    }
+    if config.prng_warmup != 0 {
+        status!(config, "[*] PRNG warm-up: {} outputs discarded", config.prng_warmup);
+    }
+    let params = ProofParams {
+        width: config.width,
+        height: config.height,
+        steps: config.steps,
+        algo: config.hash_algo,
+        iterations: config.iterations,
+        warmup: config.prng_warmup,
+    };
+    let initial_grid = params.try_seed_grid(&config.username, &config.password)?;
+    status!(config, "[*] Initial Grid Hash: {}", hash_grid(&initial_grid));
+    // A healthy seed is ~50% Ink; far off that means a degenerate seed.
+    let initial_density = initial_grid.count_alive() as f64 / initial_grid.as_raw().len().max(1) as f64;
+    status!(config, "[*] Initial density: {:.1}%", initial_density * 100.0);
+    // The simulation consumes the grid, so keep a copy for the artifact.
+    let seed = config.embed_seed.then(|| initial_grid.clone());
+
+    // Optional: settle caches and clock speed on throwaway runs first, so the
+    // timing below is steady-state. Kept out of the reported duration.
+    let warmup_time = run_warmups(&config, &initial_grid);
 
     // 3. Simulation (The "Work")
-    println!("[2] Running Simulation...");
-    let final_grid = run_simulation(initial_grid, config.steps);
-    
-    let duration = start_time.elapsed();
-    println!("[*] Completed in {:.2?}", duration);
+    status!(config, "[2] Running Simulation...");
+    install_interrupt_handler();
+    let sim_start = Instant::now();
+    let mut timeline = None;
+    let (final_grid, outcome) = match (&config.animation, &config.dump) {
+        (Some(animation), _) => {
+            status!(config, "[*] Recording animation to '{}'...", animation.output_file);
+            run_animated(initial_grid, config.steps, animation, &INTERRUPTED)
+                .map_err(CliError::io(format!("failed to write animation '{}'", animation.output_file)))?
+        }
+        (None, Some(dump)) => {
+            status!(config, "[*] Dumping every {} generation(s) to '{}'...", dump.every, dump.dir);
+            run_dumped(initial_grid, config.steps, dump, config.alive_test, &INTERRUPTED)?
+        }
+        (None, None) => match config.timeline_every {
+            Some(every) => {
+                let (grid, outcome, samples) = run_with_timeline(initial_grid, config.steps, every, &INTERRUPTED);
+                timeline = Some(samples);
+                (grid, outcome)
+            }
+            None => run_simulation_until(initial_grid, config.steps, &INTERRUPTED),
+        },
+    };
+    // The run can stop early, so only count the ticks that happened.
+    let ticks = match outcome {
+        RunOutcome::Completed => config.steps,
+        RunOutcome::Extinct(step) | RunOutcome::Interrupted(step) => step,
+    };
+    let metrics = SimMetrics::new(final_grid.as_raw().len(), ticks, sim_start.elapsed());
+
+    // Ctrl-C: report how far the run got, but never pass it off as the proof.
+    if let RunOutcome::Interrupted(step) = outcome {
+        let hash = config.hash_algo.hash_grid(&final_grid, config.context.as_bytes());
+        status!(config, "[3] Partial Grid Hash (step {}): {}", step, hash);
+        return Err(CliError::Interrupted { step, steps: config.steps });
+    }
+
+    // An all-dead grid hashes the same for every seed, so the proof is worthless.
+    if let RunOutcome::Extinct(step) = outcome {
+        if config.reject_extinct {
+            return Err(CliError::Rejected(format!(
+                "the grid went extinct at step {}.\n       \
+                 Pick different parameters (grid size, steps or credentials).",
+                step
+            )));
+        }
+        eprintln!("[!] Warning: the grid went extinct at step {}; this proof is not unique.", step);
+    }
+
+    let duration = start_time.elapsed().saturating_sub(warmup_time);
+    if config.warmup_runs > 0 {
+        status!(config, "[*] Completed in {:.2?} (warmed, after {} throwaway runs)", duration, config.warmup_runs);
+        status!(config, "[*] Throughput (warmed): {:.1} M cells/s", metrics.cells_per_sec / 1e6);
+    } else {
+        status!(config, "[*] Completed in {:.2?}", duration);
+        status!(config, "[*] Throughput: {:.1} M cells/s", metrics.cells_per_sec / 1e6);
+    }
 
     // 4. Hashing (The "Verification")
-    let hash = calculate_grid_hash(&final_grid);
-    println!("[3] Final Grid Hash: {}", hash);
+    if !config.context.is_empty() {
+        status!(config, "[*] Context: {}", config.context);
+    }
+    let hash = config.hash_algo.hash_grid(&final_grid, config.context.as_bytes());
+    status!(config, "[3] Final Grid Hash: {}", hash);
+
+    let proof = format_proof(&params, &hash);
+    status!(config, "[*] Proof: {}", proof);
+
+    if let Some(path) = &config.proof_file {
+        let mut artifact = ProofArtifact::from_proof(&proof).expect("Engine produced an invalid proof");
+        if let Some(seed) = seed {
+            artifact = artifact.with_seed(seed);
+        }
+        std::fs::write(path, artifact.to_bytes())
+            .map_err(CliError::io(format!("failed to write proof artifact '{}'", path)))?;
+        status!(config, "[*] Proof artifact written to '{}'", path);
+    }
+
+    if config.short {
+        let artifact = ProofArtifact::from_proof(&proof).expect("Engine produced an invalid proof");
+        status!(config, "[*] Short proof: {}", artifact.to_short());
+    }
+
+    if config.stats {
+        print_stats(&config, &final_grid);
+    }
+
+    if let Some(path) = &config.components_file {
+        let (labels, count) = final_grid.connected_components(Neighborhood::Moore);
+        save_labeled_image(&labels, path).map_err(CliError::io(format!("failed to write component map '{}'", path)))?;
+        status!(config, "[*] Component map ({} components) written to '{}'", count, path);
+    }
+
+    // Optional: eyeball the result without opening the image.
+    // Always stderr, so it never mixes into a streamed image.
+    if let Some(limit) = config.preview_limit {
+        eprint!("{}", final_grid.preview(limit));
+    }
 
     // 5. Visualization (The "Proof")
-    println!("[4] Saving visual proof to '{}'...", config.output_file);
-    save_ppm_image(&final_grid, &config.output_file).expect("Failed to write image");
+    if config.output_file == STDOUT_PATH {
+        status!(config, "[4] Streaming visual proof to stdout...");
+    } else {
+        status!(config, "[4] Saving visual proof to '{}'...", config.output_file);
+    }
+    save_ppm_image(&final_grid, &config.output_file, config.alive_test)
+        .map_err(CliError::io(format!("failed to write image '{}'", config.output_file)))?;
+
+    if config.json {
+        let timeline = timeline.as_deref();
+        println!("{}", json_summary(&config, &hash, &proof, initial_density, &final_grid, duration, timeline));
+    }
+
+    status!(config, "--- Done ---");
+    Ok(())
+}
+
+/// `--with-timeline`: same result as `run_simulation_until`, plus the
+/// population sampled like `run_simulation_population`: entry `i` is the
+/// population after tick `min((i + 1) * every, steps)`. A grid that dies out
+/// stays dead, so an extinct run's remaining samples are 0 and the timeline
+/// always has `ceil(steps / every)` entries (exactly `steps` at `every = 1`).
+fn run_with_timeline(
+    mut grid: Grid<u8>,
+    steps: usize,
+    every: usize,
+    stop: &AtomicBool,
+) -> (Grid<u8>, RunOutcome, Vec<usize>) {
+    let mut timeline = Vec::with_capacity(steps.div_ceil(every));
+    let mut done = 0;
+    while done < steps {
+        let batch = every.min(steps - done);
+        let (next, outcome) = run_simulation_until(grid, batch, stop);
+        grid = next;
+
+        match outcome {
+            RunOutcome::Completed => done += batch,
+            RunOutcome::Interrupted(step) => return (grid, RunOutcome::Interrupted(done + step), timeline),
+            RunOutcome::Extinct(step) => {
+                timeline.resize(steps.div_ceil(every), 0);
+                return (grid, RunOutcome::Extinct(done + step), timeline);
+            }
+        }
+        timeline.push(grid.count_alive());
+    }
+    (grid, RunOutcome::Completed, timeline)
+}
+
+/// `--json`: the proof as one JSON object, with the seed's ink fraction
+/// (`initial_density`, 0 to 1) and the population timeline (see
+/// `run_with_timeline`) when one was recorded.
+fn json_summary(
+    config: &Config,
+    hash: &str,
+    proof: &str,
+    initial_density: f64,
+    final_grid: &Grid<u8>,
+    duration: Duration,
+    timeline: Option<&[usize]>,
+) -> String {
+    let mut json = format!(
+        "{{\"username\":{},\"width\":{},\"height\":{},\"steps\":{},\"hash\":{},\"proof\":{},\
+         \"initial_density\":{:.4},\"final_population\":{},\"elapsed_ms\":{:.3}",
+        json_string(&config.username),
+        config.width,
+        config.height,
+        config.steps,
+        json_string(hash),
+        json_string(proof),
+        initial_density,
+        final_grid.count_alive(),
+        duration.as_secs_f64() * 1e3
+    );
+    if let (Some(every), Some(timeline)) = (config.timeline_every, timeline) {
+        let samples: Vec<String> = timeline.iter().map(usize::to_string).collect();
+        json.push_str(&format!(",\"timeline_every\":{},\"timeline\":[{}]", every, samples.join(",")));
+    }
+    json.push('}');
+    json
+}
+
+/// `text` as a JSON string literal, quotes included.
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// `--warmup`: runs `config.warmup_runs` simulations of at most
+/// `WARMUP_RUN_STEPS` ticks on copies of `grid` and discards them. Returns the
+/// time they took, so the reported duration can leave it out.
+fn run_warmups(config: &Config, grid: &Grid<u8>) -> Duration {
+    let start = Instant::now();
+    if config.warmup_runs == 0 {
+        return Duration::ZERO;
+    }
+    let steps = config.steps.min(WARMUP_RUN_STEPS);
+    status!(config, "[*] Warming up: {} throwaway run(s) of {} steps...", config.warmup_runs, steps);
+    for _ in 0..config.warmup_runs {
+        std::hint::black_box(run_simulation(grid.clone(), steps));
+    }
+    start.elapsed()
+}
+
+/// First Ctrl-C asks the simulation to stop after the current tick; a second
+/// one exits immediately, in case a single tick of a huge grid takes too long.
+fn install_interrupt_handler() {
+    let result = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+        eprintln!("\n[!] Interrupt received, stopping after the current tick (Ctrl-C again to abort)...");
+    });
+    if let Err(err) = result {
+        eprintln!("[!] Warning: Ctrl-C will abort without a partial result: {}", err);
+    }
+}
+
+/// `--stats`: population, block entropy, extent, and how many cells have each
+/// live-neighbor count.
+fn print_stats(config: &Config, grid: &Grid<u8>) {
+    let total = grid.as_raw().len().max(1) as f64;
+    let stats = grid.stats();
+    status!(config, "[*] Stats: {} ink cells ({:.1}%)", stats.alive, stats.density * 100.0);
+    status!(config, "    2x2 block entropy: {:.3} bits", stats.entropy);
+    match stats.bounding_box {
+        Some((x, y, w, h)) => status!(config, "    Bounding box: {}x{} at ({}, {})", w, h, x, y),
+        None => status!(config, "    Bounding box: none (no ink)"),
+    }
+    status!(config, "    Neighbors  Cells");
+    for (neighbors, &cells) in grid.neighbor_histogram(Neighborhood::Moore).iter().enumerate() {
+        status!(config, "    {:>9}  {} ({:.1}%)", neighbors, cells, cells as f64 * 100.0 / total);
+    }
+}
+
+/// `--dry-run`: estimates the memory and time the proof would take, without running it.
+fn print_dry_run(config: &Config) {
+    // `parse_args` already checked that the cell count fits.
+    let grid_bytes = config.width * config.height * mem::size_of::<u8>();
+    // Each tick reads the current grid while filling a freshly allocated next one.
+    let peak_bytes = estimate_footprint(config.width, config.height, 1, StorageKind::Bytes)
+        .expect("parse_args checked the grid size");
+
+    status!(config, "[*] Dry run: nothing will be simulated or written.");
+    status!(
+        config,
+        "[*] Memory: {} per grid, ~{} peak (double buffer)",
+        format_bytes(grid_bytes),
+        format_bytes(peak_bytes)
+    );
+    status!(
+        config,
+        "[*] Estimated time: ~{:.2?}",
+        estimate_runtime(config.width, config.height, config.steps)
+    );
+}
+
+/// Formats a byte count with binary units (B, KiB, MiB, ...).
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Same result as `run_simulation_until`, but streams frames into a GIF along
+/// the way. Once `max_frames` are written the remaining steps run in one go.
+/// An interrupted run keeps the frames recorded so far.
+fn run_animated(
+    mut grid: Grid<u8>,
+    steps: usize,
+    animation: &Animation,
+    stop: &AtomicBool,
+) -> io::Result<(Grid<u8>, RunOutcome)> {
+    let first = grid.downsample(animation.scale);
+    let file = BufWriter::new(File::create(&animation.output_file)?);
+    let mut gif = GifWriter::new(file, first.width(), first.height(), PAPER_WHITE, INK_BLACK)?;
+    gif.frame(&first)?;
+
+    let max_frames = animation.max_frames.unwrap_or(usize::MAX);
+    let mut frames = 1;
+    let mut done = 0;
+    while done < steps {
+        let recording = frames < max_frames;
+        let batch = if recording { animation.frame_every.min(steps - done) } else { steps - done };
+        let (next, outcome) = run_simulation_until(grid, batch, stop);
+        grid = next;
+
+        match outcome {
+            RunOutcome::Interrupted(step) => {
+                gif.finish()?;
+                return Ok((grid, RunOutcome::Interrupted(done + step)));
+            }
+            _ if recording => {
+                gif.frame(&grid.downsample(animation.scale))?;
+                frames += 1;
+            }
+            _ => {}
+        }
+        if let RunOutcome::Extinct(step) = outcome {
+            gif.finish()?;
+            return Ok((grid, RunOutcome::Extinct(done + step)));
+        }
+        done += batch;
+    }
 
-    println!("--- Done ---");
+    gif.finish()?;
+    Ok((grid, RunOutcome::Completed))
 }
 
-/// Hashes the raw bytes of the grid to create a verification string.
-fn calculate_grid_hash(grid: &Grid<u8>) -> String {
-    let mut hasher = Sha256::new();
-    // We hash the entire flat vector of cells
-    hasher.update(grid.as_raw());
-    let result = hasher.finalize();
-    hex::encode(result)
+/// Same result as `run_simulation_until`, but writes the seed and every
+/// `dump.every`-th generation to `dump.dir` as `frame_000123.ppm` (the step,
+/// zero-padded to six digits), creating the directory first. The seed frame is
+/// written before any ticks, so an unusable directory fails fast. An
+/// interrupted or extinct run keeps the frames written so far.
+fn run_dumped(
+    mut grid: Grid<u8>,
+    steps: usize,
+    dump: &FrameDump,
+    alive_test: AliveTest,
+    stop: &AtomicBool,
+) -> Result<(Grid<u8>, RunOutcome), CliError> {
+    std::fs::create_dir_all(&dump.dir)
+        .map_err(CliError::io(format!("failed to create dump directory '{}'", dump.dir)))?;
+    let write_frame = |grid: &Grid<u8>, step: usize| {
+        let path = Path::new(&dump.dir).join(format!("frame_{:06}.ppm", step));
+        let path = path.to_string_lossy();
+        save_ppm_image(grid, &path, alive_test).map_err(CliError::io(format!("failed to write frame '{}'", path)))
+    };
+    write_frame(&grid, 0)?;
+
+    let mut done = 0;
+    while done < steps {
+        let batch = dump.every.min(steps - done);
+        let (next, outcome) = run_simulation_until(grid, batch, stop);
+        grid = next;
+
+        match outcome {
+            RunOutcome::Completed => done += batch,
+            RunOutcome::Interrupted(step) => return Ok((grid, RunOutcome::Interrupted(done + step))),
+            RunOutcome::Extinct(step) => return Ok((grid, RunOutcome::Extinct(done + step))),
+        }
+        if done % dump.every == 0 {
+            write_frame(&grid, done)?;
+        }
+    }
+    Ok((grid, RunOutcome::Completed))
+}
+
+/// The limits `verify` and `audit` hold a proof to before re-running it: the
+/// same default step cap as proving, and `VerifyLimits`' default grid size.
+fn default_verify_limits() -> VerifyLimits {
+    VerifyLimits { max_steps: DEFAULT_MAX_STEPS, ..VerifyLimits::default() }
+}
+
+/// `verify <username> <password> <proof>`: re-derives the proof with the parameters
+/// embedded in the proof string. An invalid proof is `CliError::Rejected` (exit code 1);
+/// one over `--max-steps` / `--max-cells` is refused before anything is built (exit code 3).
+fn run_verify(args: &[String]) -> Result<(), CliError> {
+    let mut positional = Vec::new();
+    let mut context = String::new();
+    let mut limits = default_verify_limits();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--context" => context = iter.next().ok_or("--context needs a value.")?.clone(),
+            "--max-steps" => limits.max_steps = parse_count(iter.next().cloned(), "--max-steps")?,
+            "--max-cells" => limits.max_cells = parse_count(iter.next().cloned(), "--max-cells")?,
+            _ => positional.push(arg),
+        }
+    }
+
+    let [username, password, proof] = positional[..] else {
+        return Err("verify expects <username> <password> <proof> [--context <text>] [--max-steps <n>] \
+                    [--max-cells <n>]."
+            .into());
+    };
+
+    // A `.ivp` argument is a binary artifact on disk; anything else is a proof string.
+    let artifact = if proof.ends_with(".ivp") {
+        let bytes = std::fs::read(proof).map_err(CliError::io(format!("cannot read proof artifact '{}'", proof)))?;
+        ProofArtifact::from_bytes(&bytes)?
+    } else {
+        ProofArtifact::from_proof(proof)?
+    };
+
+    if verify_artifact_with_limits(username, password, &artifact, context.as_bytes(), &limits)? {
+        println!("[+] VALID proof for user '{}'", username);
+        Ok(())
+    } else {
+        println!("[-] INVALID proof for user '{}'", username);
+        Err(CliError::Rejected(format!("proof does not match user '{}'", username)))
+    }
+}
+
+/// `decode <token>`: expands a `--short` token back into readable parameters
+/// and the full proof string (which `verify` accepts).
+fn run_decode(args: &[String]) -> Result<(), CliError> {
+    let [token] = args else {
+        return Err("decode expects a single <token>.".into());
+    };
+
+    let proof = ProofArtifact::from_short(token)?.to_proof();
+    let (params, hash) = parse_proof(&proof)?;
+    println!("[*] Grid:       {}x{}", params.width, params.height);
+    println!("[*] Steps:      {}", params.steps);
+    println!("[*] Iterations: {}", params.iterations);
+    println!("[*] Warm-up:    {}", params.warmup);
+    println!("[*] Hash:       {} {}", params.algo, hash);
+    println!("[*] Proof:      {}", proof);
+    Ok(())
+}
+
+/// `audit <file.ivp>`: replays an `--embed-seed` artifact from its seed grid,
+/// no credentials needed. A mismatch is `CliError::Rejected` (exit code 1);
+/// limits are as for `verify`.
+fn run_audit(args: &[String]) -> Result<(), CliError> {
+    let mut positional = Vec::new();
+    let mut context = String::new();
+    let mut limits = default_verify_limits();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--context" => context = iter.next().ok_or("--context needs a value.")?.clone(),
+            "--max-steps" => limits.max_steps = parse_count(iter.next().cloned(), "--max-steps")?,
+            "--max-cells" => limits.max_cells = parse_count(iter.next().cloned(), "--max-cells")?,
+            _ => positional.push(arg),
+        }
+    }
+
+    let [path] = positional[..] else {
+        return Err("audit expects <file.ivp> [--context <text>] [--max-steps <n>] [--max-cells <n>].".into());
+    };
+
+    let bytes = std::fs::read(path).map_err(CliError::io(format!("cannot read proof artifact '{}'", path)))?;
+    let artifact = ProofArtifact::from_bytes(&bytes)?;
+    println!("[*] Proof: {}", artifact.to_proof());
+    limits.check(&artifact.params).map_err(ProofError::OverLimit)?;
+    if artifact.verify_seed_with_context(context.as_bytes())? {
+        println!("[+] VALID: the digest follows from the embedded seed");
+        Ok(())
+    } else {
+        println!("[-] INVALID: the digest does not follow from the embedded seed");
+        Err(CliError::Rejected(format!("artifact '{}' does not replay to its digest", path)))
+    }
+}
+
+/// `selftest`: recomputes every pinned test vector with this build, so a deployer
+/// can check that their features and platform still produce canonical hashes.
+/// Any mismatch is `CliError::Rejected` (exit code 1).
+fn run_selftest() -> Result<(), CliError> {
+    println!("--- InkVerify Self-Test ---");
+    let features = if cfg!(feature = "parallel") { "parallel" } else { "default" };
+    println!("[*] Build features: {}", features);
+
+    let vectors = known_vectors();
+    let mut failed = 0;
+    for vector in vectors {
+        let mut label = format!("{} {}x{} {} steps", vector.username, vector.width, vector.height, vector.steps);
+        if vector.endian == SeedEndian::Little {
+            label.push_str(" (little-endian seed)");
+        }
+        let hash = vector.compute_hash();
+        if hash == vector.expected_hash {
+            println!("[PASS] {}", label);
+        } else {
+            println!("[FAIL] {}: expected {}, got {}", label, vector.expected_hash, hash);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(CliError::Rejected(format!(
+            "{} of {} test vectors failed; this build does not produce canonical proofs.",
+            failed,
+            vectors.len()
+        )));
+    }
+    println!("[*] All {} test vectors passed.", vectors.len());
+    Ok(())
+}
+
+/// `bench [rounds]`: proves and verifies every pinned test vector `rounds`
+/// times (default 5) and reports the time and throughput of each half, for
+/// tracking performance across engine changes. Build with `--release` for
+/// meaningful numbers. A vector that misses its hash is `CliError::Rejected`.
+fn run_bench(args: &[String]) -> Result<(), CliError> {
+    let rounds = match args {
+        [] => DEFAULT_BENCH_ROUNDS,
+        [rounds] => parse_count(Some(rounds.clone()), "bench")?,
+        _ => return Err("bench expects at most one [rounds] count.".into()),
+    };
+
+    println!("--- InkVerify Vector Benchmark ---");
+    let features = if cfg!(feature = "parallel") { "parallel" } else { "default" };
+    println!("[*] Build features: {}", features);
+    println!("[*] Vectors: {} x {} rounds", known_vectors().len(), rounds);
+
+    let report = benchmark_vectors(rounds);
+    println!("[*] Prove:  {:.2?}", report.prove_time);
+    println!("[*] Verify: {:.2?}", report.verify_time);
+    println!(
+        "[*] Total:  {:.2?} for {} cell updates ({:.1} M cells/s)",
+        report.total_time(),
+        report.cells_updated,
+        report.cells_per_sec() / 1e6
+    );
+
+    if report.failures > 0 {
+        return Err(CliError::Rejected(format!(
+            "{} of {} vector runs missed their pinned hash; run selftest for details.",
+            report.failures, report.runs
+        )));
+    }
+    Ok(())
+}
+
+/// Runs the full proof (Seed -> Simulation -> Hash) for one credential pair.
+fn prove_credentials(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    steps: usize,
+) -> Result<String, GridError> {
+    let initial_grid = try_generate_grid_from_seed(username, password, width, height)?;
+    let final_grid = run_simulation(initial_grid, steps);
+    Ok(hash_grid(&final_grid))
+}
+
+/// Proves every `username,password` row of the input CSV and writes `username,hash` rows.
+///
+/// Malformed rows are skipped and reported by line number only,
+/// so passwords never end up in the logs. Only the line terminator is
+/// stripped: leading or trailing spaces in a password are part of it.
+/// Parallel builds prove the rows concurrently; the output keeps input order.
+fn run_batch(config: &BatchConfig) -> Result<(), CliError> {
+    let read_error = || CliError::io(format!("failed to read '{}'", config.input_file));
+    let write_error = || CliError::io(format!("failed to write '{}'", config.output_file));
+    let input = BufReader::new(File::open(&config.input_file).map_err(read_error())?);
+    let mut output = BufWriter::new(File::create(&config.output_file).map_err(write_error())?);
+
+    println!("--- InkVerify Batch ---");
+    println!("[*] Grid: {}x{}, Steps: {}", config.width, config.height, config.steps);
+
+    let mut rows = Vec::new();
+    let mut skipped = 0;
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(read_error())?;
+        // `lines` drops "\n" and "\r\n"; a stray "\r" before the newline is still a terminator.
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+
+        // Blank lines and an optional header row are not credentials.
+        if line.is_empty() || line.eq_ignore_ascii_case("username,password") {
+            continue;
+        }
+
+        match parse_credentials(line) {
+            Some((username, password)) => rows.push((username.to_string(), password.to_string())),
+            None => {
+                eprintln!("[!] Skipping malformed line {}", index + 1);
+                skipped += 1;
+            }
+        }
+    }
+
+    for ((username, _), hash) in rows.iter().zip(prove_rows(&rows, config)?) {
+        writeln!(output, "{},{}", username, hash).map_err(write_error())?;
+    }
+    output.flush().map_err(write_error())?;
+
+    println!("[*] Proved {} credential(s), skipped {} malformed line(s)", rows.len(), skipped);
+    println!("[*] Wrote '{}'", config.output_file);
+    Ok(())
+}
+
+/// The batch proofs for `rows`, in the same order, one row per rayon task.
+#[cfg(feature = "parallel")]
+fn prove_rows(rows: &[(String, String)], config: &BatchConfig) -> Result<Vec<String>, GridError> {
+    use rayon::prelude::*;
+
+    rows.par_iter()
+        .map(|(username, password)| prove_credentials(username, password, config.width, config.height, config.steps))
+        .collect()
+}
+
+/// The batch proofs for `rows`, in the same order.
+#[cfg(not(feature = "parallel"))]
+fn prove_rows(rows: &[(String, String)], config: &BatchConfig) -> Result<Vec<String>, GridError> {
+    rows.iter()
+        .map(|(username, password)| prove_credentials(username, password, config.width, config.height, config.steps))
+        .collect()
+}
+
+/// Splits a CSV row into (username, password).
+/// The username may not contain commas; the password keeps everything after the first one.
+fn parse_credentials(line: &str) -> Option<(&str, &str)> {
+    let (username, password) = line.split_once(',')?;
+    let username = username.trim();
+    if username.is_empty() {
+        return None;
+    }
+    Some((username, password))
+}
+
+/// Opens the image destination: a file, or stdout when the path is "-".
+/// Both are handed out as the same `Box<dyn Write>` so a single code path serves them.
+fn open_output(filename: &str) -> io::Result<Box<dyn Write>> {
+    if filename == STDOUT_PATH {
+        Ok(Box::new(BufWriter::new(io::stdout().lock())))
+    } else {
+        Ok(Box::new(BufWriter::new(File::create(filename)?)))
+    }
 }
 
 /// Saves the grid as a standard Netpbm (.ppm) image.
 /// This format is supported by most image viewers (like Photoshop, GIMP, Preview)
 /// and requires NO external libraries to write.
-fn save_ppm_image(grid: &Grid<u8>, filename: &str) -> std::io::Result<()> {
-    let mut file = File::create(filename)?;
-    
-    // P3 = Text-based PPM header
-    // Width Height
-    // Max Color Value (255)
-    writeln!(file, "P3\n{} {}\n255", grid.width(), grid.height())?;
-
-    for y in 0..grid.height() {
-        for x in 0..grid.width() {
-            let cell = grid.get(x as isize, y as isize);
-            
-            // Color Logic:
-            // Alive (1) = Black (0 0 0)
-            // Dead (0)  = White (255 255 255)
-            // You can customize this to make "Ink" look blue/purple!
-            let (r, g, b) = if cell == 1 {
-                (0, 0, 0) // Black
-            } else {
-                (255, 255, 255) // White
-            };
-
-            write!(file, "{} {} {} ", r, g, b)?;
-        }
-        writeln!(file, "")?; // Newline for file structure
-    }
-    Ok(())
+fn save_ppm_image(grid: &Grid<u8>, filename: &str, alive_test: AliveTest) -> std::io::Result<()> {
+    let mut file = open_output(filename)?;
+    file.write_all(&encode_ppm_as(grid, alive_test))?;
+    file.flush()
+}
+
+/// Saves a label grid (component ids, states) as a .ppm with one distinct,
+/// stable color per label (`palette_color`); label 0 is the white background.
+fn save_labeled_image(labels: &Grid<u32>, filename: &str) -> std::io::Result<()> {
+    let mut file = open_output(filename)?;
+    file.write_all(&encode_ppm_labels(labels))?;
+    file.flush()
 }
 
 /// Minimal argument parser.
-/// Expects: ./cli <username> <password> [width] [height] [steps]
-fn parse_args() -> Result<Config, String> {
-    let args: Vec<String> = env::args().collect();
+/// Expects: ./cli <username> <password> [width] [height] [steps] [--flags]
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut args = args.iter().cloned();
+    let mut positional: Vec<String> = Vec::new();
+    let mut target_time = None;
+    let mut output_file = "proof.ppm".to_string();
+    let mut min_password_bits = None;
+    let mut preview_limit = None;
+    let mut alive_test = AliveTest::NonZero;
+    let mut reject_extinct = false;
+    let mut animation_file = None;
+    let mut frame_every = 1;
+    let mut max_frames = None;
+    let mut scale = 1;
+    let mut dump_dir = None;
+    let mut dump_every = None;
+    let mut difficulty = None;
+    let mut dry_run = false;
+    let mut stats = false;
+    let mut proof_file = None;
+    let mut embed_seed = false;
+    let mut short = false;
+    let mut hash_algo = HashAlgo::Sha256;
+    let mut threads = None;
+    let mut max_steps = DEFAULT_MAX_STEPS;
+    let mut iterations = 1;
+    let mut prng_warmup = 0;
+    let mut warmup_runs = 0;
+    let mut components_file = None;
+    let mut json = false;
+    let mut with_timeline = false;
+    let mut timeline_every = None;
+    let mut size = None;
+    let mut context = String::new();
+
+    // Flags may appear anywhere; everything else is positional.
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output_file = args.next().ok_or("--output needs a file path (or '-').")?,
+            "--stdout" => output_file = STDOUT_PATH.to_string(),
+            "--min-password-entropy" => {
+                let value = args.next().ok_or("--min-password-entropy needs a number of bits.")?;
+                let bits = value
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid entropy threshold '{}'.", value))?;
+                min_password_bits = Some(bits);
+            }
+            "--preview" => preview_limit = preview_limit.or(Some(PREVIEW_LIMIT)),
+            "--preview-limit" => {
+                let value = args.next().ok_or("--preview-limit needs a number of cells.")?;
+                let limit = value
+                    .parse()
+                    .map_err(|_| format!("Invalid preview limit '{}'.", value))?;
+                preview_limit = Some(limit);
+            }
+            "--alive-test" => {
+                alive_test = match args.next().as_deref() {
+                    Some("nonzero") => AliveTest::NonZero,
+                    Some("brightness") => AliveTest::Brightness,
+                    _ => return Err("--alive-test expects 'nonzero' or 'brightness'.".to_string()),
+                };
+            }
+            "--reject-extinct" => reject_extinct = true,
+            "--dry-run" => dry_run = true,
+            "--stats" => stats = true,
+            "--json" => json = true,
+            "--with-timeline" => with_timeline = true,
+            "--timeline-every" => timeline_every = Some(parse_count(args.next(), "--timeline-every")?),
+            "--hash" => {
+                let value = args.next().ok_or("--hash needs an algorithm (sha256, sha512, sha256t<bytes>).")?;
+                hash_algo = value.parse()?;
+            }
+            "--components" => components_file = Some(args.next().ok_or("--components needs a .ppm file path.")?),
+            "--output-proof" => proof_file = Some(args.next().ok_or("--output-proof needs a file path.")?),
+            "--embed-seed" => embed_seed = true,
+            "--short" => short = true,
+            "--context" => context = args.next().ok_or("--context needs a value.")?,
+            "--size" => size = Some(parse_size(args.next())?),
+            "--difficulty" => {
+                let value = args.next().ok_or("--difficulty needs a level.")?;
+                let level = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&level| level <= MAX_DIFFICULTY_LEVEL)
+                    .ok_or(format!("Difficulty must be 0-{}, got '{}'.", MAX_DIFFICULTY_LEVEL, value))?;
+                difficulty = Some(level);
+            }
+            "--animate" => animation_file = Some(args.next().ok_or("--animate needs a .gif file path.")?),
+            "--frame-every" => frame_every = parse_count(args.next(), "--frame-every")?,
+            "--max-frames" => max_frames = Some(parse_count(args.next(), "--max-frames")?),
+            "--scale" => scale = parse_count(args.next(), "--scale")?,
+            "--dump-dir" => dump_dir = Some(args.next().ok_or("--dump-dir needs a directory path.")?),
+            "--dump-every" => dump_every = Some(parse_count(args.next(), "--dump-every")?),
+            "--threads" => threads = Some(parse_count(args.next(), "--threads")?),
+            "--max-steps" => max_steps = parse_count(args.next(), "--max-steps")?,
+            "--iterations" => {
+                let count = parse_count(args.next(), "--iterations")?;
+                iterations = u32::try_from(count).map_err(|_| format!("--iterations must be at most {}.", u32::MAX))?;
+            }
+            "--prng-warmup" => {
+                let value = args.next().ok_or("--prng-warmup needs a number of PRNG outputs.")?;
+                prng_warmup = value.parse().map_err(|_| format!("Invalid --prng-warmup count '{}'.", value))?;
+            }
+            "--warmup" => warmup_runs = parse_count(args.next(), "--warmup")?,
+            "--target-time" => {
+                let value = args.next().ok_or("--target-time needs a value (e.g. 1s).")?;
+                target_time = Some(parse_duration(&value)?);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown flag '{}'.", flag));
+            }
+            _ => positional.push(arg),
+        }
+    }
 
-    if args.len() < 3 {
+    if positional.len() < 2 {
         return Err("Not enough arguments.".to_string());
     }
 
-    let username = args[1].clone();
-    let password = args[2].clone();
+    let username = positional[0].clone();
+    let password = positional[1].clone();
     
-    // Defaults
-    let width = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(200);
-    let height = args.get(4).and_then(|s| s.parse().ok()).unwrap_or(200);
-    let steps = args.get(5).and_then(|s| s.parse().ok()).unwrap_or(500);
-    let output_file = "proof.ppm".to_string();
+    // A difficulty level picks all three sizes; `--size` picks the grid and
+    // leaves [steps] as the only positional; otherwise they come from the
+    // command line (or the defaults).
+    let (width, height, steps) = match (difficulty, size) {
+        (Some(_), Some(_)) => return Err("--difficulty already sets the grid size; drop --size.".to_string()),
+        (Some(_), None) if positional.len() > 2 => {
+            return Err("--difficulty replaces [width] [height] [steps]; pass one or the other.".to_string());
+        }
+        (Some(level), None) => params_for_difficulty(level),
+        (None, Some(_)) if positional.len() > 3 => {
+            return Err("--size replaces [width] [height]; pass only [steps] after the credentials.".to_string());
+        }
+        (None, Some((width, height))) => {
+            (width, height, positional.get(2).and_then(|s| s.parse().ok()).unwrap_or(500))
+        }
+        (None, None) => (
+            positional.get(2).and_then(|s| s.parse().ok()).unwrap_or(200),
+            positional.get(3).and_then(|s| s.parse().ok()).unwrap_or(200),
+            positional.get(4).and_then(|s| s.parse().ok()).unwrap_or(500),
+        ),
+    };
+    // Reject sizes the engine cannot allocate here, not as a panic mid-run.
+    cell_count(width, height).map_err(|err| err.to_string())?;
+
+    if embed_seed && proof_file.is_none() {
+        return Err("--embed-seed needs --output-proof <file.ivp>.".to_string());
+    }
+
+    let animation = match animation_file {
+        Some(output_file) => Some(Animation { output_file, frame_every, max_frames, scale }),
+        None if frame_every != 1 || max_frames.is_some() || scale != 1 => {
+            return Err("--frame-every, --max-frames and --scale need --animate <file.gif>.".to_string());
+        }
+        None => None,
+    };
+
+    let dump = match (dump_dir, dump_every) {
+        (Some(_), _) if animation.is_some() => {
+            return Err("--dump-dir and --animate both record the run; pick one.".to_string());
+        }
+        (Some(dir), every) => Some(FrameDump { dir, every: every.unwrap_or(1) }),
+        (None, Some(_)) => return Err("--dump-every needs --dump-dir <dir>.".to_string()),
+        (None, None) => None,
+    };
+
+    let timeline_every = match (with_timeline, timeline_every) {
+        (true, _) if !json => return Err("--with-timeline needs --json.".to_string()),
+        (true, _) if animation.is_some() || dump.is_some() => {
+            return Err("--with-timeline cannot be combined with --animate or --dump-dir.".to_string());
+        }
+        (true, every) => Some(every.unwrap_or(1)),
+        (false, Some(_)) => return Err("--timeline-every needs --with-timeline.".to_string()),
+        (false, None) => None,
+    };
+    if json && output_file == STDOUT_PATH {
+        return Err("--json and --output - both write to stdout; pick one.".to_string());
+    }
+
+    if threads.is_some() && !cfg!(feature = "parallel") {
+        return Err("--threads needs a build with the `parallel` feature.".to_string());
+    }
 
     Ok(Config {
         username,
@@ -125,12 +1112,256 @@ fn parse_args() -> Result<Config, String> {
         height,
         steps,
         output_file,
+        target_time,
+        min_password_bits,
+        preview_limit,
+        alive_test,
+        reject_extinct,
+        animation,
+        dump,
+        dry_run,
+        context,
+        stats,
+        proof_file,
+        embed_seed,
+        short,
+        hash_algo,
+        threads,
+        max_steps,
+        iterations,
+        prng_warmup,
+        warmup_runs,
+        components_file,
+        json,
+        timeline_every,
     })
 }
 
+/// Parses the value of a flag that takes a positive whole number.
+fn parse_count(value: Option<String>, flag: &str) -> Result<usize, String> {
+    let value = value.ok_or(format!("{} needs a number.", flag))?;
+    match value.parse() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!("{} expects a positive number, got '{}'.", flag, value)),
+    }
+}
+
+/// Parses: batch --input <file> --output <file> [--size WxH] [width] [height] [steps]
+fn parse_batch_args(args: &[String]) -> Result<BatchConfig, String> {
+    let mut args = args.iter().cloned();
+    let mut positional: Vec<String> = Vec::new();
+    let mut input_file = None;
+    let mut output_file = None;
+    let mut size = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input_file = Some(args.next().ok_or("--input needs a file path.")?),
+            "--output" => output_file = Some(args.next().ok_or("--output needs a file path.")?),
+            "--size" => size = Some(parse_size(args.next())?),
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown flag '{}'.", flag));
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    // With `--size`, [steps] is the only positional.
+    let ((width, height), steps) = match size {
+        Some(_) if positional.len() > 1 => {
+            return Err("--size replaces [width] [height]; pass only [steps].".to_string());
+        }
+        Some(size) => (size, positional.first()),
+        None => (
+            (
+                positional.first().and_then(|s| s.parse().ok()).unwrap_or(200),
+                positional.get(1).and_then(|s| s.parse().ok()).unwrap_or(200),
+            ),
+            positional.get(2),
+        ),
+    };
+    let config = BatchConfig {
+        input_file: input_file.ok_or("batch mode needs --input <file>.")?,
+        output_file: output_file.ok_or("batch mode needs --output <file>.")?,
+        width,
+        height,
+        steps: steps.and_then(|s| s.parse().ok()).unwrap_or(500),
+    };
+    cell_count(config.width, config.height).map_err(|err| err.to_string())?;
+    Ok(config)
+}
+
+/// Parses the value of `--size`: `WxH` (or `WXH`), e.g. `500x500` or
+/// ` 640 x 480 `. Both sides must be positive whole numbers.
+fn parse_size(value: Option<String>) -> Result<(usize, usize), String> {
+    let value = value.ok_or("--size needs a WxH value (e.g. 500x500).")?;
+    let (width, height) = value
+        .trim()
+        .split_once(['x', 'X'])
+        .ok_or(format!("--size expects WxH (e.g. 500x500), got '{}'.", value))?;
+    let side = |text: &str, name: &str| match text.trim().parse::<usize>() {
+        Ok(0) => Err(format!("--size {} must be positive, got '{}'.", name, value)),
+        Ok(side) => Ok(side),
+        Err(_) if text.trim().is_empty() => Err(format!("--size is missing the {} in '{}'.", name, value)),
+        Err(_) => Err(format!("--size {} '{}' is not a whole number.", name, text.trim())),
+    };
+    Ok((side(width, "width")?, side(height, "height")?))
+}
+
+/// Parses a human-friendly duration such as `1s`, `1.5s`, `250ms` or `2m`.
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, scale) = if let Some(n) = text.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = text.strip_suffix('s') {
+        (n, 1.0)
+    } else if let Some(n) = text.strip_suffix('m') {
+        (n, 60.0)
+    } else {
+        return Err(format!("Invalid duration '{}' (expected e.g. 1s, 250ms).", text));
+    };
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}'.", text))?;
+    if !value.is_finite() || value <= 0.0 {
+        return Err(format!("Duration '{}' must be positive.", text));
+    }
+    Ok(Duration::from_secs_f64(value * scale))
+}
+
 fn print_usage() {
     println!("Usage:");
-    println!("  cargo run -- <username> <password> [width] [height] [steps]");
+    println!("  cargo run -- <username> <password> [--size WxH] [steps] [options]");
+    println!("  cargo run -- <username> <password> [width] [height] [steps] [options]");
+    println!("  cargo run -- batch --input <users.csv> --output <proofs.csv> [--size WxH] [steps]");
+    println!("  cargo run -- verify <username> <password> <proof | file.ivp> [--context <text>] [limits]");
+    println!("  cargo run -- selftest            Check this build against the pinned test vectors");
+    println!("  cargo run -- bench [rounds]      Time proving and verifying the test vectors (default 5 rounds)");
+    println!("  cargo run -- decode <token>      Expand a --short proof token");
+    println!("  cargo run -- audit <file.ivp> [--context <text>]  Replay an --embed-seed artifact, no credentials");
+    println!("Options:");
+    println!("  --size <WxH>          Grid size, e.g. 500x500 (default 200x200; replaces [width] [height])");
+    println!("  --difficulty <level>  Use the standard grid size and steps for <level> (0-{})", MAX_DIFFICULTY_LEVEL);
+    println!("  --hash <algo>         Final digest: sha256 (default), sha512 or sha256t<bytes> (8-32)");
+    println!("  --iterations <n>      Stretch the seed with <n> SHA-256 rounds (default 1; recorded in the proof)");
+    println!("  --prng-warmup <n>     Discard <n> PRNG outputs before seeding (default 0; recorded in the proof)");
+    println!("  --context <text>      Bind the proof to a challenge/session (verify needs the same)");
+    println!("  --dry-run             Print estimated memory and run time, then exit");
+    println!("  --threads <n>         Use at most <n> worker threads (parallel builds; default all cores)");
+    println!("  --max-steps <n>       Refuse runs longer than <n> steps (default {})", DEFAULT_MAX_STEPS);
+    let max_cells = VerifyLimits::default().max_cells;
+    println!("  --max-cells <n>       verify/audit: refuse proofs over <n> cells (default {})", max_cells);
+    println!("  --target-time <dur>   Calibrate steps to take roughly <dur> (e.g. 1s, 250ms)");
+    println!("  --warmup <n>          Run <n> short throwaway simulations first, then time a warmed run");
+    println!("  --output <file>       Image path (default proof.ppm); '-' streams to stdout");
+    println!("  --output-proof <file> Also save the proof as a compact binary artifact (.ivp)");
+    println!("  --embed-seed          Store the seed grid in the artifact so 'audit' can replay it");
+    println!("  --short               Also print the proof as a short base32 token (shareable, QR-friendly)");
+    println!("  --stdout              Same as --output -");
+    println!("  --min-password-entropy <bits>  Refuse passwords estimated below <bits> of entropy");
+    println!("  --components <file>   Also save a color-coded map of the final grid's connected blobs (.ppm)");
+    println!("  --stats               Print population and neighbor-count histogram after the run");
+    println!("  --json                Print a JSON summary of the proof to stdout (progress goes to stderr)");
+    println!("  --with-timeline       JSON: include the population after every tick");
+    println!("  --timeline-every <n>  Timeline: keep every <n>th tick's population (default 1)");
+    println!("  --preview             Print an ASCII preview of the final grid to stderr");
+    println!("  --preview-limit <n>   Preview at most <n> cells per side (0 = whole grid)");
+    println!("  --alive-test <mode>   Image coloring: 'nonzero' (default) or 'brightness'");
+    println!("  --animate <file.gif>  Also record the run as an animated GIF");
+    println!("  --frame-every <n>     Animation: keep every <n>th generation (default 1)");
+    println!("  --max-frames <n>      Animation: stop recording after <n> frames");
+    println!("  --scale <n>           Animation: shrink frames <n>x (a block with any Ink stays Ink)");
+    println!("  --dump-dir <dir>      Also write generations to <dir> as frame_000000.ppm, ...");
+    println!("  --dump-every <n>      Dump: keep every <n>th generation (default 1)");
+    println!("  --reject-extinct      Fail if the grid dies out completely (the proof would be worthless)");
     println!("Example:");
-    println!("  cargo run -- Alice MySecretPass 500 500 1000");
+    println!("  cargo run -- Alice MySecretPass --size 500x500 1000");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_has_one_entry_per_tick_ending_at_final_population() {
+        let seed = try_generate_grid_from_seed("alice", "secret", 24, 24).unwrap();
+        let stop = AtomicBool::new(false);
+        let (grid, outcome, timeline) = run_with_timeline(seed.clone(), 37, 1, &stop);
+
+        assert_eq!(outcome, RunOutcome::Completed);
+        assert_eq!(timeline.len(), 37);
+        assert_eq!(timeline.last(), Some(&grid.count_alive()));
+        assert_eq!(grid, run_simulation(seed, 37));
+    }
+
+    #[test]
+    fn sampled_timeline_stays_bounded() {
+        let seed = try_generate_grid_from_seed("alice", "secret", 24, 24).unwrap();
+        let stop = AtomicBool::new(false);
+        let (grid, _, timeline) = run_with_timeline(seed, 37, 10, &stop);
+
+        assert_eq!(timeline.len(), 4);
+        assert_eq!(timeline.last(), Some(&grid.count_alive()));
+    }
+
+    #[test]
+    fn extinct_timeline_is_padded_with_zeros() {
+        let stop = AtomicBool::new(false);
+        let (_, outcome, timeline) = run_with_timeline(Grid::new(8, 8), 5, 2, &stop);
+
+        assert_eq!(outcome, RunOutcome::Extinct(0));
+        assert_eq!(timeline, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn batch_keeps_order_and_password_whitespace() {
+        let dir = env::temp_dir().join(format!("inkverify-batch-{}", process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("in.csv"), dir.join("out.csv"));
+        std::fs::write(&input, "username,password\r\nalice, secret \r\nbob,hunter2\n\nnocomma\ncarol,pw\t\n").unwrap();
+
+        let config = BatchConfig {
+            input_file: input.to_string_lossy().into_owned(),
+            output_file: output.to_string_lossy().into_owned(),
+            width: 16,
+            height: 16,
+            steps: 8,
+        };
+        run_batch(&config).unwrap();
+
+        // Each row must hash exactly like a single `prove` run of the same pair.
+        let params = ProofParams { width: 16, height: 16, steps: 8, algo: HashAlgo::Sha256, iterations: 1, warmup: 0 };
+        let prove = |username, password| params.prove(username, password);
+        let expected = format!(
+            "alice,{}\nbob,{}\ncarol,{}\n",
+            prove("alice", " secret "),
+            prove("bob", "hunter2"),
+            prove("carol", "pw\t")
+        );
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn size_tokens_parse_width_by_height() {
+        let size = |text: &str| parse_size(Some(text.to_string()));
+        assert_eq!(size("500x500"), Ok((500, 500)));
+        assert_eq!(size("640X480"), Ok((640, 480)));
+        assert_eq!(size(" 16 x 9 "), Ok((16, 9)));
+
+        assert_eq!(size("500x"), Err("--size is missing the height in '500x'.".to_string()));
+        assert_eq!(size("x500"), Err("--size is missing the width in 'x500'.".to_string()));
+        assert_eq!(size("abc"), Err("--size expects WxH (e.g. 500x500), got 'abc'.".to_string()));
+        assert_eq!(size("0x10"), Err("--size width must be positive, got '0x10'.".to_string()));
+        assert_eq!(size("10x-3"), Err("--size height '-3' is not a whole number.".to_string()));
+        assert_eq!(size("2x3x4"), Err("--size height '3x4' is not a whole number.".to_string()));
+        assert!(parse_size(None).is_err());
+    }
 }
@@ -0,0 +1,271 @@
+// End-to-end tests: run the built binary the way a user would.
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use inkverify_core::{generate_grid_from_seed, hash_grid, known_vectors, run_simulation};
+
+/// A fresh scratch directory for one test, so default outputs like
+/// `proof.ppm` never land in the source tree.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("inkverify-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the CLI in `dir` with `args`.
+fn inkverify(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_inkverify-cli"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run the CLI")
+}
+
+/// Splits a P3 image into (width, height, rgb triplets).
+fn parse_p3(bytes: &[u8]) -> (usize, usize, Vec<[u8; 3]>) {
+    let text = std::str::from_utf8(bytes).expect("P3 is text");
+    let mut tokens = text.split_whitespace();
+    assert_eq!(tokens.next(), Some("P3"));
+    let mut number = || tokens.next().expect("truncated PPM").parse::<usize>().unwrap();
+    let (width, height, max) = (number(), number(), number());
+    assert_eq!(max, 255);
+    let values: Vec<u8> = tokens.map(|token| token.parse().unwrap()).collect();
+    assert_eq!(values.len(), width * height * 3);
+    (width, height, values.chunks(3).map(|rgb| [rgb[0], rgb[1], rgb[2]]).collect())
+}
+
+#[test]
+fn stdout_output_is_a_clean_ppm_stream() {
+    let dir = scratch_dir("stdout");
+    let output = inkverify(&dir, &["alice", "secret", "8", "6", "10", "--stdout"]);
+
+    assert!(output.status.success());
+    let (width, height, pixels) = parse_p3(&output.stdout);
+    assert_eq!((width, height), (8, 6));
+    assert!(pixels.iter().all(|&rgb| rgb == [0, 0, 0] || rgb == [255, 255, 255]));
+    // Progress still goes somewhere: stderr.
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Final Grid Hash"));
+    assert!(!dir.join("proof.ppm").exists());
+}
+
+#[test]
+fn preview_prints_the_whole_small_grid_to_stderr() {
+    let dir = scratch_dir("preview");
+    let output = inkverify(&dir, &["alice", "secret", "8", "8", "10", "--preview"]);
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let preview: Vec<&str> = stderr.lines().skip_while(|line| !line.starts_with("Grid Preview (8x8)")).collect();
+    assert!(preview.len() > 8, "no preview on stderr:\n{}", stderr);
+    assert!(preview[1..=8].iter().all(|row| row.chars().count() == 8));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Grid Preview"));
+}
+
+#[test]
+fn dry_run_estimates_without_writing() {
+    let dir = scratch_dir("dry-run");
+    let output = inkverify(&dir, &["alice", "secret", "1024", "1024", "500", "--dry-run"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[*] Memory: 1.0 MiB per grid, ~2.0 MiB peak"), "{}", stdout);
+    assert!(stdout.contains("[*] Estimated time:"));
+    assert!(!stdout.contains("Final Grid Hash"));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+}
+
+#[test]
+fn unwritable_output_is_a_clean_io_error() {
+    let dir = scratch_dir("unwritable");
+    let target = dir.join("missing").join("proof.ppm");
+    let output = inkverify(&dir, &["alice", "secret", "8", "8", "5", "--output", target.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error: failed to write image"), "{}", stderr);
+    assert!(!stderr.contains("panicked"));
+    assert!(!target.exists());
+}
+
+#[test]
+fn oversized_grid_is_a_usage_error() {
+    let dir = scratch_dir("oversized");
+    let output = inkverify(&dir, &["alice", "secret", "4294967296", "4294967296", "1"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("exceeds the maximum cell count"), "{}", stderr);
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn thread_count_does_not_change_the_proof() {
+    let dir = scratch_dir("threads");
+    let args = |threads| ["alice", "secret", "300", "300", "20", "--output", "-", "--threads", threads];
+    let one = inkverify(&dir, &args("1"));
+    let four = inkverify(&dir, &args("4"));
+
+    assert!(one.status.success() && four.status.success());
+    // Status lines go to stderr when the image is on stdout.
+    let proof = |output: &Output| {
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        stderr.lines().find(|line| line.starts_with("[*] Proof: ")).expect("no proof line").to_string()
+    };
+    assert_eq!(proof(&one), proof(&four));
+    assert_eq!(one.stdout, four.stdout);
+}
+
+#[test]
+#[cfg(not(feature = "parallel"))]
+fn threads_need_the_parallel_feature() {
+    let dir = scratch_dir("threads");
+    let output = inkverify(&dir, &["alice", "secret", "8", "8", "5", "--threads", "4"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--threads needs a build with the `parallel` feature."));
+}
+
+#[test]
+#[cfg(unix)]
+fn interrupt_reports_the_partial_hash() {
+    let dir = scratch_dir("interrupt");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_inkverify-cli"))
+        .args(["alice", "secret", "256", "256", "1000000"])
+        .current_dir(&dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the CLI");
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.starts_with("[2] Running Simulation") {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "the CLI exited before simulating");
+    }
+    // Give the CLI time to install its handler and run a few ticks.
+    thread::sleep(Duration::from_millis(300));
+    let kill = Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(kill.success());
+
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+    let status = child.wait().unwrap();
+    let mut stderr = String::new();
+    child.stderr.take().unwrap().read_to_string(&mut stderr).unwrap();
+
+    assert_eq!(status.code(), Some(130));
+    let partial = rest.lines().find_map(|line| line.strip_prefix("[3] Partial Grid Hash (step ")).expect(&rest);
+    let (step, hash) = partial.split_once("): ").unwrap();
+    let step: usize = step.parse().unwrap();
+    assert!(step > 0 && step < 1_000_000);
+    assert!(stderr.contains(&format!("interrupted at step {} of 1000000", step)), "{}", stderr);
+    assert_eq!(hash, hash_grid(&run_simulation(generate_grid_from_seed("alice", "secret", 256, 256), step)));
+    assert!(!dir.join("proof.ppm").exists());
+}
+
+#[test]
+fn initial_density_is_reported_near_half() {
+    let dir = scratch_dir("density");
+    let output = inkverify(&dir, &["alice", "secret", "200", "200", "1"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let density = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("[*] Initial density: "))
+        .and_then(|value| value.strip_suffix('%'))
+        .expect("no density line");
+    let density: f64 = density.parse().unwrap();
+    assert!((45.0..55.0).contains(&density), "{}", density);
+
+    // `--json` carries the same density, as a fraction.
+    let output = inkverify(&dir, &["alice", "secret", "200", "200", "1", "--json"]);
+    assert!(output.status.success());
+    let json = String::from_utf8_lossy(&output.stdout);
+    let field = json.split("\"initial_density\":").nth(1).expect("no initial_density field");
+    let fraction: f64 = field.split(',').next().unwrap().parse().unwrap();
+    assert!((fraction * 100.0 - density).abs() < 0.06, "{} vs {}%", fraction, density);
+}
+
+#[test]
+fn selftest_passes_every_vector() {
+    let dir = scratch_dir("selftest");
+    let output = inkverify(&dir, &["selftest"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let vectors = known_vectors().len();
+    assert_eq!(stdout.lines().filter(|line| line.starts_with("[PASS] ")).count(), vectors);
+    assert!(!stdout.contains("[FAIL]"));
+    assert!(stdout.contains(&format!("[*] All {} test vectors passed.", vectors)));
+}
+
+#[test]
+fn dump_dir_holds_one_named_frame_per_dump() {
+    let dir = scratch_dir("dump");
+    let args = ["alice", "secret", "24", "24", "10", "--dump-dir", "frames/run", "--dump-every", "3"];
+    let output = inkverify(&dir, &args);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let mut names: Vec<String> = fs::read_dir(dir.join("frames/run"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["frame_000000.ppm", "frame_000003.ppm", "frame_000006.ppm", "frame_000009.ppm"]);
+
+    let seed = generate_grid_from_seed("alice", "secret", 24, 24);
+    let (_, _, pixels) = parse_p3(&fs::read(dir.join("frames/run/frame_000009.ppm")).unwrap());
+    let ink = pixels.iter().filter(|&&rgb| rgb == [0, 0, 0]).count();
+    assert_eq!(ink, run_simulation(seed.clone(), 9).count_alive());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let hash = format!("[3] Final Grid Hash: {}", hash_grid(&run_simulation(seed, 10)));
+    assert!(stdout.contains(&hash), "{}", stdout);
+}
+
+#[test]
+fn warmup_keeps_the_hash_and_labels_the_timing() {
+    let dir = scratch_dir("warmup");
+    let cold = inkverify(&dir, &["alice", "secret", "32", "32", "40", "--output", "cold.ppm"]);
+    let warm = inkverify(&dir, &["alice", "secret", "32", "32", "40", "--output", "warm.ppm", "--warmup", "3"]);
+
+    assert!(cold.status.success() && warm.status.success());
+    let (cold_out, warm_out) = (String::from_utf8_lossy(&cold.stdout), String::from_utf8_lossy(&warm.stdout));
+    let hash = |stdout: &str| stdout.lines().find(|line| line.starts_with("[3] Final Grid Hash: ")).map(str::to_owned);
+    assert!(hash(&cold_out).is_some());
+    assert_eq!(hash(&cold_out), hash(&warm_out));
+    assert_eq!(fs::read(dir.join("cold.ppm")).unwrap(), fs::read(dir.join("warm.ppm")).unwrap());
+
+    assert!(warm_out.contains("[*] Warming up: 3 throwaway run(s)"), "{}", warm_out);
+    assert!(warm_out.contains("(warmed, after 3 throwaway runs)"), "{}", warm_out);
+    assert!(!cold_out.contains("warmed"), "{}", cold_out);
+}
+
+#[test]
+fn oversized_proofs_are_refused_before_verifying() {
+    let dir = scratch_dir("oversized-proof");
+    let hash = "ab".repeat(32);
+    for proof in [format!("v2:9223372036854775807x2:1:{}", hash), format!("v2:8x8:999999999999:{}", hash)] {
+        let output = inkverify(&dir, &["verify", "alice", "secret", &proof]);
+
+        assert_eq!(output.status.code(), Some(3), "{}", proof);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Proof is over the verifier's limits"), "{}", stderr);
+        assert!(!stderr.contains("panicked"));
+    }
+
+    // The step limit can be lowered (or raised) per call.
+    let proof = format!("v2:8x8:50:{}", hash);
+    let output = inkverify(&dir, &["verify", "alice", "secret", &proof, "--max-steps", "10"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("50 steps exceeds the limit of 10"));
+}
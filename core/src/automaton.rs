@@ -0,0 +1,106 @@
+use crate::grid::Grid;
+use crate::rules::{Rule, MOORE_OFFSETS};
+
+// --- Generic Cellular Automata ---
+//
+// `tick` is hard-wired to binary Life on `Grid<u8>`. This is the prototyping
+// path for richer state machines (e.g. water / ink / dried ink): a transition
+// sees the center cell and its 8 Moore neighbors on the torus, in
+// `MOORE_OFFSETS` order, and may use any cell type. It trades the fast row
+// path for generality, so proofs keep going through `run_simulation`.
+
+/// A cellular automaton: how a cell evolves given its neighbors.
+pub trait CellAutomaton {
+    type Cell: Copy + Default;
+
+    /// Returns the next state of `center`. `neighbors` holds the 8 surrounding
+    /// cells in `MOORE_OFFSETS` order (top-left first, row by row).
+    fn step(neighbors: &[Self::Cell], center: Self::Cell) -> Self::Cell;
+}
+
+/// Today's Ink rule (B3/S23 on `u8` cells) as a `CellAutomaton`.
+/// `run_automaton::<LifeAutomaton>` reproduces `run_simulation` exactly.
+pub struct LifeAutomaton;
+
+impl CellAutomaton for LifeAutomaton {
+    type Cell = u8;
+
+    fn step(neighbors: &[u8], center: u8) -> u8 {
+        let count = neighbors.iter().filter(|&&cell| cell == 1).count() as u8;
+        Rule::INK.apply(center, count)
+    }
+}
+
+/// Runs `steps` generations of automaton `A` on a torus.
+pub fn run_automaton<A: CellAutomaton>(mut grid: Grid<A::Cell>, steps: usize) -> Grid<A::Cell> {
+    for _ in 0..steps {
+        grid = step_automaton::<A>(&grid);
+    }
+    grid
+}
+
+/// One generation of `A`: a plain per-cell scan through the wrapping `get`.
+fn step_automaton<A: CellAutomaton>(current: &Grid<A::Cell>) -> Grid<A::Cell> {
+    let width = current.width();
+    let height = current.height();
+    let mut next = Grid::with_layout(width, height, current.layout());
+    let mut neighbors = [A::Cell::default(); MOORE_OFFSETS.len()];
+
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            for (slot, (dx, dy)) in neighbors.iter_mut().zip(MOORE_OFFSETS.iter()) {
+                *slot = current.get(x + dx, y + dy);
+            }
+            next.set(x as usize, y as usize, A::step(&neighbors, current.get(x, y)));
+        }
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::{generate_grid_from_seed, run_simulation};
+
+    /// Brian's Brain: off (0) cells with exactly 2 firing (1) neighbors fire,
+    /// firing cells start dying (2), dying cells switch off.
+    struct BriansBrain;
+
+    impl CellAutomaton for BriansBrain {
+        type Cell = u8;
+
+        fn step(neighbors: &[u8], center: u8) -> u8 {
+            match center {
+                0 if neighbors.iter().filter(|&&cell| cell == 1).count() == 2 => 1,
+                1 => 2,
+                _ => 0,
+            }
+        }
+    }
+
+    /// Parses rows of '.' (off), '#' (firing) and '+' (dying).
+    fn brain(rows: &[&str]) -> Grid<u8> {
+        let cells = rows.iter().flat_map(|row| row.chars()).map(|c| match c {
+            '#' => 1,
+            '+' => 2,
+            _ => 0,
+        });
+        Grid::from_raw(rows[0].len(), rows.len(), cells.collect())
+    }
+
+    #[test]
+    fn three_state_automaton_steps_by_its_own_rule() {
+        let start = brain(&["......", "......", "..##..", "......", "......", "......"]);
+        let once = brain(&["......", "..##..", "..++..", "..##..", "......", "......"]);
+        let twice = brain(&["..##..", "..++..", ".#..#.", "..++..", "..##..", "......"]);
+        assert_eq!(run_automaton::<BriansBrain>(start.clone(), 1), once);
+        assert_eq!(run_automaton::<BriansBrain>(start, 2), twice);
+    }
+
+    #[test]
+    fn life_automaton_matches_run_simulation() {
+        let seed = generate_grid_from_seed("alice", "secret", 20, 15);
+        assert_eq!(run_automaton::<LifeAutomaton>(seed.clone(), 8), run_simulation(seed, 8));
+    }
+}
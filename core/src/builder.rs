@@ -0,0 +1,144 @@
+use crate::engine::{credential_hasher, grid_from_digest, run_simulation_with, HashAlgo, DEFAULT_DENSITY};
+use crate::grid::{Boundary, Grid};
+use crate::rules::{Neighborhood, Rule};
+use sha2::{Digest, Sha256};
+
+/// The ergonomic front door for customized proofs.
+///
+/// Every setting defaults to today's behavior, so
+/// `SimulationBuilder::default().prove(user, pass, 500, 500, steps)` is exactly
+/// `prove_work(user, pass, steps)`. Anything changed here changes the proof, so the
+/// verifier must use the same settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationBuilder {
+    rule: Rule,
+    neighborhood: Neighborhood,
+    boundary: Boundary,
+    density: u8,
+    salt: Vec<u8>,
+    lanes: usize,
+}
+
+impl Default for SimulationBuilder {
+    fn default() -> Self {
+        SimulationBuilder {
+            rule: Rule::INK,
+            neighborhood: Neighborhood::Moore,
+            boundary: Boundary::Torus,
+            density: DEFAULT_DENSITY,
+            salt: Vec::new(),
+            lanes: 1,
+        }
+    }
+}
+
+impl SimulationBuilder {
+    /// The B/S rule applied every tick (default B3/S23).
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Which cells count as neighbors (default Moore).
+    pub fn neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// What reads past the edge see (default torus).
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Seeding density: each cell starts as Ink with probability `density / 256`
+    /// (default `DEFAULT_DENSITY`, just under 50%).
+    pub fn density(mut self, density: u8) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Extra bytes mixed into the seed hash after the credentials (default empty).
+    pub fn salt(mut self, salt: &[u8]) -> Self {
+        self.salt = salt.to_vec();
+        self
+    }
+
+    /// Number of independent grids ("lanes") per proof (default 1).
+    ///
+    /// Each lane is seeded with its index mixed in after the salt, simulated on its
+    /// own, and the lane digests are hashed together. More lanes multiply the work
+    /// per proof; lanes run one after another, so peak memory stays that of one
+    /// lane (see `estimate_footprint`). Panics if `lanes` is 0.
+    pub fn lanes(mut self, lanes: usize) -> Self {
+        assert!(lanes > 0, "A proof needs at least one lane");
+        self.lanes = lanes;
+        self
+    }
+
+    /// Seeds the initial grid for `lane` from the credentials and the salt.
+    pub fn seed(&self, username: &str, password: &str, width: usize, height: usize, lane: usize) -> Grid<u8> {
+        let mut hasher = credential_hasher(username, password);
+        hasher.update(&self.salt);
+        // A single lane keeps the classic seed, so the default matches `prove_work`.
+        if self.lanes > 1 {
+            hasher.update((lane as u64).to_le_bytes());
+        }
+        grid_from_digest(&hasher.finalize(), width, height, self.density)
+    }
+
+    /// Runs `steps` ticks with the configured rule, neighborhood and boundary.
+    pub fn run(&self, grid: Grid<u8>, steps: usize) -> Grid<u8> {
+        run_simulation_with(grid, steps, &self.rule, self.neighborhood, self.boundary)
+    }
+
+    /// Seeds, simulates and hashes every lane, returning the hex proof.
+    pub fn prove(&self, username: &str, password: &str, width: usize, height: usize, steps: usize) -> String {
+        let lane_digests: Vec<Vec<u8>> = (0..self.lanes)
+            .map(|lane| {
+                let final_grid = self.run(self.seed(username, password, width, height, lane), steps);
+                HashAlgo::Sha256.digest(&final_grid, &[])
+            })
+            .collect();
+
+        match lane_digests.as_slice() {
+            [single] => hex::encode(single),
+            lanes => hex::encode(Sha256::digest(lanes.concat())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{generate_grid_from_seed, hash_grid, run_simulation};
+
+    #[test]
+    fn default_builder_is_the_standard_proof() {
+        let builder = SimulationBuilder::default();
+        assert_eq!(builder.prove("alice", "secret", 500, 500, 3), crate::prove_work("alice", "secret", 3));
+
+        let standard = hash_grid(&run_simulation(generate_grid_from_seed("alice", "secret", 40, 30), 20));
+        assert_eq!(builder.prove("alice", "secret", 40, 30, 20), standard);
+    }
+
+    #[test]
+    fn every_setting_changes_the_proof() {
+        let standard = SimulationBuilder::default().prove("alice", "secret", 40, 30, 20);
+        let custom = [
+            SimulationBuilder::default().rule(Rule::new(&[3, 6], &[2, 3])),
+            SimulationBuilder::default().neighborhood(Neighborhood::VonNeumann),
+            SimulationBuilder::default().boundary(Boundary::Dead),
+            SimulationBuilder::default().density(64),
+            SimulationBuilder::default().salt(b"pepper"),
+            SimulationBuilder::default().lanes(2),
+        ];
+        let mut hashes: Vec<String> =
+            custom.iter().map(|builder| builder.prove("alice", "secret", 40, 30, 20)).collect();
+        hashes.push(standard);
+        let count = hashes.len();
+        hashes.sort();
+        hashes.dedup();
+        assert_eq!(hashes.len(), count);
+    }
+}
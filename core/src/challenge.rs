@@ -0,0 +1,125 @@
+use crate::engine::{generate_grid_with_nonce, leading_zero_bits, run_simulation, HashAlgo};
+
+// --- Challenge / Response ---
+//
+// The server issues a `Challenge` (fresh random context, grid size, steps and
+// a difficulty target); the client answers with a `Solution` (a nonce and the
+// resulting hash); the server re-derives it with the same credentials and
+// checks it. Each attempt hashes the final grid followed by the context, so a
+// solution is only good for the challenge it was computed for, and the nonce
+// search makes solving cost `2^difficulty_bits` simulations on average.
+//
+// No transport is included: both types are plain data with public fields, to
+// be serialized however the surrounding auth flow likes.
+
+/// Shortest random context `Challenge::new` accepts, in bytes.
+pub const MIN_CHALLENGE_CONTEXT: usize = 16;
+
+/// Leading zero bits a `Challenge::new` solution needs (~256 attempts).
+pub const DEFAULT_CHALLENGE_BITS: u32 = 8;
+
+/// Grid side and step count of a `Challenge::new` challenge: small, so the
+/// nonce search rather than one huge simulation sets the cost.
+const DEFAULT_CHALLENGE_SIDE: usize = 64;
+const DEFAULT_CHALLENGE_STEPS: usize = 100;
+
+/// What the server asks the client to prove.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Challenge {
+    /// Server-chosen random bytes mixed into every attempt's hash.
+    pub context: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+    pub steps: usize,
+    /// Leading zero bits the solution's digest must have.
+    pub difficulty_bits: u32,
+}
+
+/// The client's answer to one `Challenge`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Solution {
+    /// The nonce mixed into the seed (see `generate_grid_with_nonce`).
+    pub nonce: u64,
+    /// Hex SHA-256 of the final grid followed by the challenge context.
+    pub hash: String,
+}
+
+impl Challenge {
+    /// A challenge with the default size, steps and `DEFAULT_CHALLENGE_BITS`,
+    /// bound to `rng_bytes`, which must come from a CSPRNG and never be reused.
+    /// Adjust the public fields for a harder or easier challenge.
+    ///
+    /// # Panics
+    /// If `rng_bytes` is shorter than `MIN_CHALLENGE_CONTEXT`.
+    pub fn new(rng_bytes: &[u8]) -> Challenge {
+        assert!(
+            rng_bytes.len() >= MIN_CHALLENGE_CONTEXT,
+            "Challenge context must be at least {} random bytes",
+            MIN_CHALLENGE_CONTEXT
+        );
+        Challenge {
+            context: rng_bytes.to_vec(),
+            width: DEFAULT_CHALLENGE_SIDE,
+            height: DEFAULT_CHALLENGE_SIDE,
+            steps: DEFAULT_CHALLENGE_STEPS,
+            difficulty_bits: DEFAULT_CHALLENGE_BITS,
+        }
+    }
+
+    /// Client side: searches nonces from 0 up for the first attempt meeting the
+    /// difficulty target. `None` only if the whole nonce space fails.
+    pub fn solve(&self, username: &str, password: &str) -> Option<Solution> {
+        (0..=u64::MAX).find_map(|nonce| {
+            let digest = self.attempt(username, password, nonce);
+            (leading_zero_bits(&digest) >= self.difficulty_bits).then(|| Solution {
+                nonce,
+                hash: hex::encode(digest),
+            })
+        })
+    }
+
+    /// Server side: re-derives `solution` from the credentials and checks that
+    /// it was computed for this challenge and meets its target. One simulation,
+    /// however long the search took the client.
+    ///
+    /// Takes the credentials because, as with `verify_proof`, the hash can only
+    /// be checked by recomputing it.
+    pub fn verify(&self, username: &str, password: &str, solution: &Solution) -> bool {
+        let digest = self.attempt(username, password, solution.nonce);
+        leading_zero_bits(&digest) >= self.difficulty_bits && hex::encode(digest) == solution.hash
+    }
+
+    /// Digest of one attempt: seed with `nonce`, simulate, hash with the context.
+    fn attempt(&self, username: &str, password: &str, nonce: u64) -> Vec<u8> {
+        let grid = generate_grid_with_nonce(username, password, nonce, self.width, self.height);
+        HashAlgo::Sha256.digest(&run_simulation(grid, self.steps), &self.context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cheap challenge: small grid, few steps, 4 bits (~16 attempts).
+    fn quick(context: &[u8]) -> Challenge {
+        Challenge { width: 16, height: 16, steps: 10, difficulty_bits: 4, ..Challenge::new(context) }
+    }
+
+    #[test]
+    fn solutions_verify_only_for_their_own_challenge() {
+        let challenge = quick(&[7; MIN_CHALLENGE_CONTEXT]);
+        let solution = challenge.solve("alice", "secret").unwrap();
+        assert!(challenge.verify("alice", "secret", &solution));
+        assert!(!challenge.verify("alice", "wrong", &solution));
+
+        let other = quick(&[8; MIN_CHALLENGE_CONTEXT]);
+        assert!(!other.verify("alice", "secret", &solution));
+        assert!(other.verify("alice", "secret", &other.solve("alice", "secret").unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 16 random bytes")]
+    fn short_contexts_are_refused() {
+        Challenge::new(&[0; MIN_CHALLENGE_CONTEXT - 1]);
+    }
+}
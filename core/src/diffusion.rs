@@ -0,0 +1,85 @@
+use crate::grid::Grid;
+use crate::rules::VON_NEUMANN_OFFSETS;
+
+// --- Continuous Ink Concentration (Visualization Only) ---
+//
+// Each cell holds an `f32` amount of ink that spreads to its 4 orthogonal
+// neighbors on the torus and fades by a fixed fraction per step, giving smooth
+// gradients instead of discrete Life states.
+//
+// NOT FOR PROOFS: float results depend on the platform's rounding and on how
+// the compiler orders the arithmetic, so two honest machines can disagree in
+// the last bits. Never hash a diffusion grid; proofs stay on `Grid<u8>`.
+
+/// One diffusion step.
+///
+/// Each cell moves `rate` of the way towards the mean of its 4 neighbors, then
+/// keeps `1 - decay` of the result. Diffusion alone conserves total ink (up to
+/// rounding); any positive `decay` strictly shrinks it.
+///
+/// # Panics
+///
+/// Panics unless `rate` and `decay` are both in `0.0..=1.0` (a larger rate
+/// overshoots and oscillates).
+pub fn diffuse_step(grid: &Grid<f32>, rate: f32, decay: f32) -> Grid<f32> {
+    assert!((0.0..=1.0).contains(&rate), "Diffusion rate must be in 0..=1");
+    assert!((0.0..=1.0).contains(&decay), "Decay must be in 0..=1");
+
+    let width = grid.width();
+    let height = grid.height();
+    let keep = 1.0 - decay;
+    let mut next = Grid::with_layout(width, height, grid.layout());
+
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let center = grid.get(x, y);
+            let sum: f32 = VON_NEUMANN_OFFSETS.iter().map(|&(dx, dy)| grid.get(x + dx, y + dy)).sum();
+            let mean = sum / VON_NEUMANN_OFFSETS.len() as f32;
+            next.set(x as usize, y as usize, (center + rate * (mean - center)) * keep);
+        }
+    }
+    next
+}
+
+/// Runs `steps` diffusion steps (see `diffuse_step`). Visualization only.
+pub fn run_diffusion(mut grid: Grid<f32>, steps: usize, rate: f32, decay: f32) -> Grid<f32> {
+    for _ in 0..steps {
+        grid = diffuse_step(&grid, rate, decay);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(grid: &Grid<f32>) -> f32 {
+        grid.as_raw().iter().sum()
+    }
+
+    #[test]
+    fn decaying_ink_shrinks_every_step() {
+        let mut grid: Grid<f32> = Grid::new(9, 7);
+        grid.set(4, 3, 10.0);
+        grid.set(0, 0, 5.0);
+
+        let mut mass = total(&grid);
+        for _ in 0..30 {
+            grid = diffuse_step(&grid, 0.5, 0.05);
+            let next = total(&grid);
+            assert!(next < mass, "{} did not shrink below {}", next, mass);
+            mass = next;
+        }
+        assert!(grid.as_raw().iter().all(|&ink| ink > 0.0));
+    }
+
+    #[test]
+    fn diffusion_alone_spreads_but_conserves_ink() {
+        let mut grid: Grid<f32> = Grid::new(9, 7);
+        grid.set(4, 3, 10.0);
+        let spread = run_diffusion(grid, 5, 0.5, 0.0);
+        assert!((total(&spread) - 10.0).abs() < 1e-4);
+        assert!(spread.get(4, 3) < 10.0 && spread.get(5, 3) > 0.0);
+        assert_eq!(spread.get(3, 3), spread.get(5, 3));
+    }
+}
@@ -1,110 +1,1116 @@
-use crate::grid::Grid;
-use sha2::{Digest, Sha256};
+use crate::grid::{cell_count, Boundary, CellGrid, Grid, GridError, Layout};
+use crate::rules::{Neighborhood, Rule, RuleTable, WeightedNeighborhood, MOORE_OFFSETS};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 // --- Part 1: Deterministic Randomness (The Seeder) ---
 
+/// Byte order in which the PRNG seed word is read from the master hash.
+///
+/// The canonical proof reads it big-endian. Other implementations that read it
+/// little-endian produce entirely different grids, so the choice is made
+/// explicit here for interop code that must match one or the other; proofs
+/// never mix the two.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SeedEndian {
+    /// `u32::from_be_bytes`: the canonical order.
+    #[default]
+    Big,
+    /// `u32::from_le_bytes`.
+    Little,
+}
+
+impl SeedEndian {
+    fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            SeedEndian::Big => u32::from_be_bytes(bytes),
+            SeedEndian::Little => u32::from_le_bytes(bytes),
+        }
+    }
+}
+
 /// A minimal Pseudo-Random Number Generator (Xorshift32).
 /// It allows us to turn a 32-byte hash into infinite random bytes
 /// without needing the heavy 'rand' crate.
-struct Xorshift32 {
+///
+/// Fully deterministic: the same seed bytes always produce the same stream, so
+/// engine code can reseed it from a grid hash mid-run and a verifier can replay it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DetRng {
     state: u32,
 }
 
-impl Xorshift32 {
-    fn new(seed: u32) -> Self {
+impl DetRng {
+    /// Xorshift maps 0 to 0 forever, so a zero seed is remapped to `0xDEADBEEF`.
+    /// Every zero seed therefore gives the same stream; `from_bytes` avoids
+    /// reaching this case for digests that merely start with zeros.
+    pub fn new(seed: u32) -> Self {
         // State cannot be 0, so we handle that edge case.
         let state = if seed == 0 { 0xDEADBEEF } else { seed };
-        Xorshift32 { state }
+        DetRng { state }
     }
 
-    /// Generates the next random u8 (byte) and advances state.
-    fn next_u8(&mut self) -> u8 {
+    /// Seeds from the first 4 bytes (big-endian) of `bytes`, typically a digest.
+    /// Shorter inputs are zero-padded. This is exactly how the grid seed is derived
+    /// from the master hash.
+    ///
+    /// Zero-state edge: if those 4 bytes are all zero, the seed is instead the XOR
+    /// of the remaining bytes taken as big-endian 4-byte words, so distinct
+    /// credentials whose digests start with four zero bytes still get distinct
+    /// streams instead of all collapsing onto `new(0)`. Only when that fold is
+    /// zero too does the fixed fallback of `new` apply. Any other digest is
+    /// seeded exactly as before.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::from_bytes_with(bytes, SeedEndian::Big)
+    }
+
+    /// `from_bytes` with the 4-byte words read in `endian` order (the zero-state
+    /// fold included). `SeedEndian::Big` is exactly `from_bytes`.
+    pub fn from_bytes_with(bytes: &[u8], endian: SeedEndian) -> Self {
+        let word = |chunk: &[u8]| {
+            let mut seed_bytes = [0u8; 4];
+            seed_bytes[..chunk.len()].copy_from_slice(chunk);
+            endian.read_u32(seed_bytes)
+        };
+
+        let (head, tail) = bytes.split_at(bytes.len().min(4));
+        let seed = match word(head) {
+            0 => tail.chunks(4).map(word).fold(0, |acc, w| acc ^ w),
+            seed => seed,
+        };
+        DetRng::new(seed)
+    }
+
+    /// Generates the next random u32 and advances state.
+    pub fn next_u32(&mut self) -> u32 {
         let mut x = self.state;
         x ^= x << 13;
         x ^= x >> 17;
         x ^= x << 5;
         self.state = x;
+        x
+    }
+
+    /// Generates the next random u64 from two consecutive u32 outputs (high first).
+    pub fn next_u64(&mut self) -> u64 {
+        let high = u64::from(self.next_u32());
+        let low = u64::from(self.next_u32());
+        (high << 32) | low
+    }
+
+    /// Generates the next random u8 (byte) and advances state.
+    pub fn next_u8(&mut self) -> u8 {
         // Return the lowest 8 bits as a byte (0-255)
-        (x & 0xFF) as u8
+        (self.next_u32() & 0xFF) as u8
+    }
+
+    /// Advances the state as if `next_u32` had been called `count` times, in
+    /// O(log count) instead of O(count).
+    ///
+    /// Xorshift is linear over GF(2): one step multiplies the 32-bit state by a
+    /// fixed 32x32 bit matrix, so `count` steps are that matrix raised to `count`
+    /// (computed by repeated squaring). This lets a chunk of a huge grid start
+    /// from exactly the state the sequential fill would have reached there.
+    pub fn jump(&mut self, mut count: u64) {
+        let mut power = BitMatrix::xorshift_step();
+        while count > 0 {
+            if count & 1 == 1 {
+                self.state = power.apply(self.state);
+            }
+            power = power.compose(&power);
+            count >>= 1;
+        }
     }
 
     /// Generates a random boolean (0 or 1) based on a threshold.
-    /// Returns 1 (Ink) or 0 (Empty).
-    fn next_bool(&mut self) -> u8 {
-        // 50% chance of being alive
-        if self.next_u8() > 128 { 1 } else { 0 }
+    /// Returns 1 (Ink) with probability `density / 256`, else 0 (Empty).
+    pub fn next_bool(&mut self, density: u8) -> u8 {
+        // Ink when the byte lands in the top `density` values.
+        if u16::from(self.next_u8()) >= 256 - u16::from(density) { 1 } else { 0 }
+    }
+}
+
+/// A linear map on 32-bit vectors over GF(2), stored as the images of the basis
+/// vectors (`columns[i]` = where bit `i` goes). Only used by `DetRng::jump`.
+#[derive(Clone, Copy)]
+struct BitMatrix {
+    columns: [u32; 32],
+}
+
+impl BitMatrix {
+    /// The matrix of one xorshift step.
+    fn xorshift_step() -> Self {
+        let mut columns = [0; 32];
+        for (bit, column) in columns.iter_mut().enumerate() {
+            let mut x = 1u32 << bit;
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *column = x;
+        }
+        BitMatrix { columns }
+    }
+
+    fn apply(&self, vector: u32) -> u32 {
+        (0..32)
+            .filter(|bit| vector & (1 << bit) != 0)
+            .fold(0, |acc, bit| acc ^ self.columns[bit])
+    }
+
+    /// `self * other`, i.e. apply `other` first.
+    fn compose(&self, other: &BitMatrix) -> BitMatrix {
+        BitMatrix {
+            columns: other.columns.map(|column| self.apply(column)),
+        }
+    }
+}
+
+/// A deterministic pseudo-random ordering of `0..n` (see `permutation`).
+///
+/// A 4-round Feistel network over the smallest even-bit-width index space
+/// holding `n`, with cycle-walking: indices that land outside `0..n` are fed
+/// through again until they land inside. A Feistel network is a bijection
+/// whatever its round function, so every index comes out exactly once. The
+/// space is under 4n, so each index needs fewer than 4 passes through it on
+/// average, and no table of `n` entries is ever built.
+#[derive(Clone, Debug)]
+pub struct Permutation {
+    seed: u64,
+    n: usize,
+    next: usize,
+    half_bits: u32,
+}
+
+/// Rounds of the Feistel network; 4 is enough for a well-mixed order.
+const PERMUTATION_ROUNDS: u64 = 4;
+
+/// Visits every index in `0..n` exactly once, in an order fixed by `seed` that
+/// jumps around the index space (so a pass over the cells in this order
+/// defeats caches and prefetchers). Same seed and `n`, same order.
+pub fn permutation(seed: u64, n: usize) -> Permutation {
+    // Bits needed for `n - 1`, rounded up to an even count of at least 2.
+    let bits = (usize::BITS - n.saturating_sub(1).leading_zeros()).max(2);
+    Permutation {
+        seed,
+        n,
+        next: 0,
+        half_bits: bits.div_ceil(2),
+    }
+}
+
+impl Permutation {
+    fn encrypt(&self, index: u64) -> u64 {
+        let mask = (1u64 << self.half_bits) - 1;
+        let (mut left, mut right) = (index >> self.half_bits, index & mask);
+        for round in 0..PERMUTATION_ROUNDS {
+            let key = self.seed ^ round.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            (left, right) = (right, left ^ (mix64(right ^ key) & mask));
+        }
+        (left << self.half_bits) | right
+    }
+}
+
+impl Iterator for Permutation {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next >= self.n {
+            return None;
+        }
+        let mut index = self.next as u64;
+        loop {
+            index = self.encrypt(index);
+            if index < self.n as u64 {
+                break;
+            }
+        }
+        self.next += 1;
+        Some(index as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.n - self.next;
+        (remaining, Some(remaining))
     }
 }
 
+impl ExactSizeIterator for Permutation {}
+
+/// SplitMix64 finalizer: a cheap, well-avalanching 64-bit mix.
+fn mix64(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Default seeding density: 127 of the 256 byte values (bytes above 128) become
+/// Ink, i.e. just under a 50% chance of being alive.
+pub const DEFAULT_DENSITY: u8 = 127;
+
 // --- Part 2: Grid Generation ---
 
 /// Generates the initial grid state from the user's credentials.
 /// 
 /// Process:
-/// 1. Hash (Username + Password) using SHA-256.
+/// 1. Hash the length-prefixed Username and Password using SHA-256.
 /// 2. Use the first 4 bytes of the hash to seed our Xorshift PRNG.
 /// 3. Fill the grid with deterministic noise.
+///
+/// Panics if `width * height` exceeds `MAX_CELLS` (see `try_generate_grid_from_seed`).
 pub fn generate_grid_from_seed(username: &str, password: &str, width: usize, height: usize) -> Grid<u8> {
+    generate_grid_stretched(username, password, width, height, 1)
+}
+
+/// Fallible version of `generate_grid_from_seed`: errors instead of panicking
+/// on huge dimensions.
+pub fn try_generate_grid_from_seed(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+) -> Result<Grid<u8>, GridError> {
+    try_generate_grid_warmed(username, password, width, height, 1, 0)
+}
+
+/// `generate_grid_from_seed` with key stretching: the master hash is re-hashed
+/// until SHA-256 has run `iterations` times in total (see `derive_seed`).
+/// `iterations = 1` is exactly `generate_grid_from_seed`.
+pub fn generate_grid_stretched(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    iterations: u32,
+) -> Grid<u8> {
+    generate_grid_warmed(username, password, width, height, iterations, 0)
+}
+
+/// `generate_grid_stretched` with a PRNG warm-up: the first `warmup` outputs
+/// are discarded (see `DetRng::jump`) before the first cell is drawn, so the
+/// fill starts that far into the stream. Xorshift mixes poorly for its first
+/// few outputs after a low-entropy seed word; a warm-up of 16 or so moves the
+/// grid past them. `warmup = 0` is exactly `generate_grid_stretched`.
+///
+/// The warm-up changes the grid, so it is part of the proof (`ProofParams::warmup`).
+pub fn generate_grid_warmed(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    iterations: u32,
+    warmup: u32,
+) -> Grid<u8> {
+    try_generate_grid_warmed(username, password, width, height, iterations, warmup).expect("Grid dimensions too large")
+}
+
+/// Fallible version of `generate_grid_warmed`.
+pub fn try_generate_grid_warmed(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    iterations: u32,
+    warmup: u32,
+) -> Result<Grid<u8>, GridError> {
+    let total = cell_count(width, height)?;
+    let mut rng = DetRng::from_bytes(&derive_seed(username, password, iterations));
+    rng.jump(u64::from(warmup));
+    Ok(Grid::from_raw(width, height, fill_cells(rng, total, DEFAULT_DENSITY)))
+}
+
+/// `generate_grid_from_seed` with the seed word read in `endian` order, for
+/// matching an implementation that chose the other byte order.
+/// `SeedEndian::Big` is exactly `generate_grid_from_seed`.
+pub fn generate_grid_with_endian(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    endian: SeedEndian,
+) -> Grid<u8> {
+    try_generate_grid_with_endian(username, password, width, height, endian).expect("Grid dimensions too large")
+}
+
+/// Fallible version of `generate_grid_with_endian`.
+pub fn try_generate_grid_with_endian(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    endian: SeedEndian,
+) -> Result<Grid<u8>, GridError> {
+    let total = cell_count(width, height)?;
+    let rng = DetRng::from_bytes_with(&derive_seed(username, password, 1), endian);
+    Ok(Grid::from_raw(width, height, fill_cells(rng, total, DEFAULT_DENSITY)))
+}
+
+/// The master hash with a work factor: `SHA-256` over the length-prefixed
+/// credentials (see `credential_hasher`), then `iterations - 1` further rounds
+/// of `SHA-256` over the previous digest.
+///
+/// A single hash makes every offline password guess as cheap as one SHA-256;
+/// this multiplies the cost of the seeding step by `iterations` for prover and
+/// attacker alike. Proofs record the count, so verifiers re-derive the same seed.
+///
+/// # Panics
+///
+/// Panics if `iterations` is 0.
+pub fn derive_seed(username: &str, password: &str, iterations: u32) -> [u8; 32] {
+    assert!(iterations > 0, "Seed derivation needs at least one iteration");
+
     // 1. Create the Master Hash
+    let mut digest: [u8; 32] = credential_hasher(username, password).finalize().into();
+
+    for _ in 1..iterations {
+        digest = Sha256::digest(digest).into();
+    }
+    digest
+}
+
+/// A SHA-256 hasher that has absorbed the credentials, each as its byte length
+/// (u64, little-endian) followed by its bytes, like `protocol::commit`. Plain
+/// concatenation made `("ab", "c")` and `("a", "bc")` the same seed; with the
+/// lengths in front, no two credential pairs feed the hasher the same bytes.
+/// Every seeding path starts here, then adds its own fields (nonce, salt, ...).
+pub(crate) fn credential_hasher(username: &str, password: &str) -> Sha256 {
     let mut hasher = Sha256::new();
-    hasher.update(username.as_bytes());
-    hasher.update(password.as_bytes());
+    for field in [username, password] {
+        hasher.update((field.len() as u64).to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher
+}
+
+/// Same as `generate_grid_from_seed`, but mixes a nonce (little-endian) into the
+/// master hash. Every nonce yields an unrelated grid, which is what makes the
+/// difficulty-target search below cost one full simulation per attempt.
+pub fn generate_grid_with_nonce(username: &str, password: &str, nonce: u64, width: usize, height: usize) -> Grid<u8> {
+    try_generate_grid_with_nonce(username, password, nonce, width, height).expect("Grid dimensions too large")
+}
+
+/// Fallible version of `generate_grid_with_nonce`.
+pub fn try_generate_grid_with_nonce(
+    username: &str,
+    password: &str,
+    nonce: u64,
+    width: usize,
+    height: usize,
+) -> Result<Grid<u8>, GridError> {
+    let mut hasher = credential_hasher(username, password);
+    hasher.update(nonce.to_le_bytes());
     let result = hasher.finalize();
 
-    // 2. Extract a Seed (Take the first 4 bytes to make a u32)
-    // We use standard array slicing and conversion here.
-    let seed_bytes: [u8; 4] = result[0..4].try_into().expect("Hash failed");
-    let seed_u32 = u32::from_be_bytes(seed_bytes);
+    try_grid_from_digest(&result, width, height, DEFAULT_DENSITY)
+}
+
+/// Clustered alternative to `generate_grid_from_seed`: instead of independent
+/// per-cell coin flips, stamps `droplets` solid discs of Ink onto an empty grid.
+///
+/// Centers and radii come from the same master hash and PRNG, so the layout is
+/// fully deterministic. Radii range from 1 to 1/8 of the shorter side, and discs
+/// wrap around the edges like everything else on the torus.
+pub fn generate_grid_droplets(username: &str, password: &str, width: usize, height: usize, droplets: usize) -> Grid<u8> {
+    try_generate_grid_droplets(username, password, width, height, droplets).expect("Grid dimensions too large")
+}
+
+/// Fallible version of `generate_grid_droplets`.
+pub fn try_generate_grid_droplets(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    droplets: usize,
+) -> Result<Grid<u8>, GridError> {
+    let mut grid = Grid::try_new(width, height)?;
+    if width == 0 || height == 0 {
+        return Ok(grid);
+    }
+
+    let result = credential_hasher(username, password).finalize();
+
+    let mut rng = DetRng::from_bytes(&result);
+    let max_radius = (width.min(height) / 8).max(1);
+    for _ in 0..droplets {
+        let cx = (rng.next_u32() as usize % width) as isize;
+        let cy = (rng.next_u32() as usize % height) as isize;
+        let radius = 1 + (rng.next_u32() as usize % max_radius) as isize;
+
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx * dx + dy * dy <= radius * radius {
+                    let x = (cx + dx).rem_euclid(width as isize) as usize;
+                    let y = (cy + dy).rem_euclid(height as isize) as usize;
+                    grid.set(x, y, 1);
+                }
+            }
+        }
+    }
+
+    Ok(grid)
+}
+
+/// Fixed-point one of `generate_grid_gradient`'s distance scale.
+const GRADIENT_ONE: u64 = 1 << 16;
+
+/// Seeds like `generate_grid_from_seed`, but the Ink probability varies with
+/// distance from the grid's center: `center_density / 256` at the center,
+/// falling (or rising) linearly to `edge_density / 256` at the midpoint of
+/// each edge, and staying at `edge_density` out into the corners.
+///
+/// Distance is measured relative to the grid's size (an ellipse on a
+/// non-square grid) in 16-bit fixed point with an integer square root, so
+/// every platform derives the same thresholds. Each cell still takes exactly
+/// one PRNG draw in row-major order: equal densities give the same grid as the
+/// uniform fill, and `DEFAULT_DENSITY` for both is `generate_grid_from_seed`.
+pub fn generate_grid_gradient(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    center_density: u8,
+    edge_density: u8,
+) -> Grid<u8> {
+    try_generate_grid_gradient(username, password, width, height, center_density, edge_density)
+        .expect("Grid dimensions too large")
+}
+
+/// Fallible version of `generate_grid_gradient`.
+pub fn try_generate_grid_gradient(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    center_density: u8,
+    edge_density: u8,
+) -> Result<Grid<u8>, GridError> {
+    let total = cell_count(width, height)?;
+    let rng = DetRng::from_bytes(&derive_seed(username, password, 1));
+
+    let (center, edge) = (i64::from(center_density), i64::from(edge_density));
+    let density_at = |index: usize| {
+        let (x, y) = ((index % width) as u64, (index / width) as u64);
+        // Offsets from the center in half cells, so the center of a cell is exact.
+        let dx = (2 * x + 1).abs_diff(width as u64);
+        let dy = (2 * y + 1).abs_diff(height as u64);
+        // (dx / width)^2 + (dy / height)^2, scaled by GRADIENT_ONE^2.
+        let scaled = |offset: u64, len: u64| u128::from(offset * GRADIENT_ONE / len).pow(2);
+        let radius = (scaled(dx, width as u64) + scaled(dy, height as u64)).isqrt().min(u128::from(GRADIENT_ONE));
+        (center + (edge - center) * radius as i64 / GRADIENT_ONE as i64) as u8
+    };
+    Ok(Grid::from_raw(width, height, fill_cells_with(rng, total, density_at)))
+}
+
+/// Steps 2-4 of seeding: turns a master hash into grid noise
+/// (each cell is Ink with probability `density / 256`).
+pub(crate) fn grid_from_digest(result: &[u8], width: usize, height: usize, density: u8) -> Grid<u8> {
+    try_grid_from_digest(result, width, height, density).expect("Grid dimensions too large")
+}
+
+/// Fallible version of `grid_from_digest`.
+pub(crate) fn try_grid_from_digest(result: &[u8], width: usize, height: usize, density: u8) -> Result<Grid<u8>, GridError> {
+    let total = cell_count(width, height)?;
 
-    // 3. Initialize RNG
-    let mut rng = Xorshift32::new(seed_u32);
+    // 2-3. Extract a Seed (the first 4 bytes as a u32) and initialize the RNG
+    let rng = DetRng::from_bytes(result);
 
     // 4. Fill Data Vector
-    let mut cells = Vec::with_capacity(width * height);
-    for _ in 0..(width * height) {
-        cells.push(rng.next_bool());
+    Ok(Grid::from_raw(width, height, fill_cells(rng, total, density)))
+}
+
+/// Cells handed to each worker by the parallel seeding fill.
+#[cfg(feature = "parallel")]
+const SEED_CHUNK_CELLS: usize = 64 * 1024;
+
+/// The seeding fill at a single density for every cell.
+fn fill_cells(rng: DetRng, total: usize, density: u8) -> Vec<u8> {
+    fill_cells_with(rng, total, |_| density)
+}
+
+/// The sequential seeding fill: one PRNG draw per cell, in row-major order,
+/// against the density `density_at` gives for that cell's index.
+#[cfg(not(feature = "parallel"))]
+fn fill_cells_with(mut rng: DetRng, total: usize, density_at: impl Fn(usize) -> u8) -> Vec<u8> {
+    let mut cells = Vec::with_capacity(total);
+    for index in 0..total {
+        cells.push(rng.next_bool(density_at(index)));
     }
+    cells
+}
 
-    Grid::from_raw(width, height, cells)
+/// The parallel seeding fill. Each chunk jumps its own copy of the PRNG to the
+/// chunk's first cell (one draw per cell), so the grid is bit-for-bit identical
+/// to the sequential fill regardless of thread count.
+#[cfg(feature = "parallel")]
+fn fill_cells_with(rng: DetRng, total: usize, density_at: impl Fn(usize) -> u8 + Sync) -> Vec<u8> {
+    use rayon::prelude::*;
+
+    let mut cells = vec![0u8; total];
+    cells
+        .par_chunks_mut(SEED_CHUNK_CELLS)
+        .enumerate()
+        .for_each(|(index, chunk)| {
+            let start = index * SEED_CHUNK_CELLS;
+            let mut rng = rng.clone();
+            rng.jump(start as u64);
+            for (offset, cell) in chunk.iter_mut().enumerate() {
+                *cell = rng.next_bool(density_at(start + offset));
+            }
+        });
+    cells
 }
 
 // --- Part 3: The Simulation Logic ---
 
 /// Runs the simulation for a fixed number of steps.
 /// Returns the Final Grid state.
+///
+/// Boundary semantics (these are part of the proof and must not drift):
+/// - `steps = 0` returns the input grid untouched, so its hash is the seed hash.
+/// - `steps = n` applies exactly `n` ticks; `steps = 1` is a single `tick`.
 pub fn run_simulation(mut grid: Grid<u8>, steps: usize) -> Grid<u8> {
+    if steps == 0 {
+        return grid;
+    }
+
     // We use double-buffering implicitly by creating a new grid every tick.
     // In production Rust, we might swap two buffers to save allocation,
     // but for clarity/simplicity, we just generate `next` from `current`.
     for _ in 0..steps {
+        grid = step(&grid, &Rule::INK);
+    }
+    grid
+}
+
+/// Same result as `run_simulation`, but ping-pongs between the input grid and a
+/// single scratch grid instead of allocating a new grid every tick, so memory
+/// peaks at exactly two grid buffers however many steps run.
+pub fn run_simulation_double_buffered(grid: Grid<u8>, steps: usize) -> Grid<u8> {
+    if steps == 0 {
+        return grid;
+    }
+
+    let mut current = grid;
+    let mut next = Grid::with_layout(current.width(), current.height(), current.layout());
+    for _ in 0..steps {
+        tick_into(&current, &Rule::INK, next.as_raw_mut());
+        std::mem::swap(&mut current, &mut next);
+    }
+    current
+}
+
+/// Runs `steps` ticks and returns only the hex SHA-256 of the final grid, equal
+/// to `hash_grid(&run_simulation(grid, steps))`.
+///
+/// For callers that only want the hash: the run is double-buffered (see
+/// `run_simulation_double_buffered`) and the final grid is hashed where it lies
+/// and dropped along with its scratch buffer, never handed back.
+pub fn run_simulation_hash(grid: Grid<u8>, steps: usize) -> String {
+    hash_grid(&run_simulation_double_buffered(grid, steps))
+}
+
+/// Replays steps `from_step..to_step` of a run, given `grid` as it stood after
+/// `from_step` ticks, and returns the grid after `to_step` ticks with its
+/// `Grid::logical_hash` (the same digest as `hash_grid` for seeded grids).
+///
+/// For dispute resolution: replaying `0..50` and then `50..100` from its output
+/// is the same as `0..100`, so prover and verifier can compare hashes at the
+/// midpoint of a window and bisect down to the first step where they differ,
+/// restarting each half from a checkpointed grid. The step numbers only label
+/// the window; the rule does not depend on them.
+///
+/// # Panics
+/// If `from_step > to_step`.
+pub fn replay_range(grid: Grid<u8>, from_step: usize, to_step: usize) -> (Grid<u8>, [u8; 32]) {
+    assert!(from_step <= to_step, "Replay range {}..{} runs backwards", from_step, to_step);
+    let grid = run_simulation(grid, to_step - from_step);
+    let hash = grid.logical_hash();
+    (grid, hash)
+}
+
+/// Same as `run_simulation`, but also reports whether (and when) the grid died out.
+///
+/// Returns the final grid and `Some(k)` if the grid was all-dead after `k` ticks
+/// (`Some(0)` for an already-empty seed). Under the Ink rule an empty grid stays
+/// empty forever, so the loop stops as soon as that happens: the returned grid
+/// (and its hash) is still exactly what `run_simulation` would produce. A proof on
+/// an extinct grid is worthless, since every such seed hashes identically.
+pub fn run_simulation_extinction(grid: Grid<u8>, steps: usize) -> (Grid<u8>, Option<usize>) {
+    // Nothing can set the flag, so never spend a clock read on it.
+    match run_simulation_until_checked(grid, steps, &AtomicBool::new(false), CheckInterval::Every(usize::MAX)) {
+        (grid, RunOutcome::Extinct(done)) => (grid, Some(done)),
+        (grid, _) => (grid, None),
+    }
+}
+
+/// How a `run_simulation_until` run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RunOutcome {
+    /// All requested ticks ran.
+    Completed,
+    /// The grid was all-dead after this many ticks (see `run_simulation_extinction`).
+    Extinct(usize),
+    /// `stop` was set; this many ticks had completed.
+    Interrupted(usize),
+}
+
+/// Same as `run_simulation_extinction`, with a cooperative checkpoint: `stop` is
+/// checked between ticks on the default adaptive `CheckInterval` (about every
+/// `DEFAULT_CHECK_TARGET`), so another thread (e.g. a Ctrl-C handler) can end
+/// the run early. The grid returned on interruption is the exact state after
+/// the reported number of ticks, never a half-computed one.
+pub fn run_simulation_until(grid: Grid<u8>, steps: usize, stop: &AtomicBool) -> (Grid<u8>, RunOutcome) {
+    run_simulation_until_checked(grid, steps, stop, CheckInterval::default())
+}
+
+/// `run_simulation_until`, checking `stop` on `checks` instead of the default
+/// interval (e.g. `CheckInterval::Every(1)` for before every tick). Extinction
+/// is still checked every tick, so `Extinct` always reports the exact tick.
+pub fn run_simulation_until_checked(
+    mut grid: Grid<u8>,
+    steps: usize,
+    stop: &AtomicBool,
+    checks: CheckInterval,
+) -> (Grid<u8>, RunOutcome) {
+    let mut checks = checks.schedule();
+    for done in 0..=steps {
+        if is_extinct(&grid) {
+            return (grid, RunOutcome::Extinct(done));
+        }
+        if done == steps {
+            break;
+        }
+        if checks.due(done) && stop.load(Ordering::Relaxed) {
+            return (grid, RunOutcome::Interrupted(done));
+        }
+        grid = step(&grid, &Rule::INK);
+    }
+    (grid, RunOutcome::Completed)
+}
+
+/// One generation under `rule` on any `CellGrid` (e.g. a `StackGrid`), on the
+/// Moore-neighborhood torus. A plain per-cell scan: gives exactly what `step`
+/// gives on a `Grid<u8>` with the same cells, without its fast row path.
+pub fn step_cells<G: CellGrid + Clone>(grid: &G, rule: &Rule) -> G {
+    let mut next = grid.clone();
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let (xi, yi) = (x as isize, y as isize);
+            next.set(x, y, rule.apply(grid.get(xi, yi), grid.live_neighbors(xi, yi)));
+        }
+    }
+    next
+}
+
+/// True if no cell holds Ink (stops at the first live cell).
+fn is_extinct(grid: &Grid<u8>) -> bool {
+    grid.as_raw().iter().all(|&cell| cell == 0)
+}
+
+/// Runs `steps` ticks, cycling through `rules`: tick `i` uses `rules[i % rules.len()]`.
+///
+/// Alternating rules gives a less regular trajectory than any single rule. A
+/// one-element slice is exactly `run_simulation` with that rule.
+///
+/// # Panics
+///
+/// Panics if `rules` is empty and `steps > 0`.
+pub fn run_simulation_rules(mut grid: Grid<u8>, steps: usize, rules: &[Rule]) -> Grid<u8> {
+    if steps == 0 {
+        return grid;
+    }
+    assert!(!rules.is_empty(), "At least one rule is required");
+
+    for rule in rules.iter().cycle().take(steps) {
+        grid = step(&grid, rule);
+    }
+    grid
+}
+
+/// Same as `run_simulation`, but also records how long each tick took.
+///
+/// Profiling only: the returned grid (and therefore the hash) is identical to
+/// `run_simulation`. The duration vector has exactly `steps` entries, so it can be
+/// lined up with per-step population counts.
+pub fn run_simulation_timed(mut grid: Grid<u8>, steps: usize) -> (Grid<u8>, Vec<Duration>) {
+    let mut durations = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        let start = Instant::now();
+        grid = tick(&grid);
+        durations.push(start.elapsed());
+    }
+    (grid, durations)
+}
+
+/// Default spacing between checks for `CheckInterval::Adaptive`: frequent
+/// enough to feel immediate, rare enough that the check itself is noise.
+pub const DEFAULT_CHECK_TARGET: Duration = Duration::from_millis(50);
+
+/// How often a cooperative loop (timeout, cancellation, progress) checks its
+/// condition between ticks.
+///
+/// Checking every tick costs real time on tiny grids, where a tick takes
+/// microseconds; checking rarely makes huge grids slow to react. `Every` fixes
+/// the tradeoff up front, `Adaptive` times the ticks as it goes and spaces the
+/// checks about `target` apart whatever the grid size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CheckInterval {
+    /// Check every `n` ticks (0 is treated as 1).
+    Every(usize),
+    /// Check about every `target` of wall-clock time, based on measured tick times.
+    Adaptive { target: Duration },
+}
+
+impl Default for CheckInterval {
+    fn default() -> Self {
+        CheckInterval::Adaptive { target: DEFAULT_CHECK_TARGET }
+    }
+}
+
+impl CheckInterval {
+    /// Ticks between checks when one tick takes `tick_time`: `n` for
+    /// `Every(n)`, and for `Adaptive` as many ticks as fit in `target` (at
+    /// least 1), so a cheaper (smaller) grid gets a longer interval.
+    pub fn ticks_between(&self, tick_time: Duration) -> usize {
+        match *self {
+            CheckInterval::Every(ticks) => ticks.max(1),
+            CheckInterval::Adaptive { target } => {
+                let ticks = target.as_nanos() / tick_time.as_nanos().max(1);
+                usize::try_from(ticks).unwrap_or(usize::MAX).max(1)
+            }
+        }
+    }
+
+    /// A schedule for one run under this policy.
+    pub fn schedule(self) -> CheckSchedule {
+        CheckSchedule {
+            policy: self,
+            next: 0,
+            last: None,
+        }
+    }
+}
+
+/// Running state of a `CheckInterval`: call `due(done)` before every tick and
+/// check the condition only when it returns true. The first call (tick 0) is
+/// always due, so a condition that already holds is seen before any work.
+#[derive(Clone, Debug)]
+pub struct CheckSchedule {
+    policy: CheckInterval,
+    /// Tick count at which the next check is due.
+    next: usize,
+    /// Tick count and time of the previous check (adaptive timing).
+    last: Option<(usize, Instant)>,
+}
+
+impl CheckSchedule {
+    /// True if the loop should check its condition now, `done` ticks in.
+    pub fn due(&mut self, done: usize) -> bool {
+        if done < self.next {
+            return false;
+        }
+
+        let ticks = match self.policy {
+            CheckInterval::Every(_) => self.policy.ticks_between(Duration::ZERO),
+            CheckInterval::Adaptive { .. } => {
+                let now = Instant::now();
+                // Until a tick has been timed, check again after the next one.
+                let ticks = match self.last {
+                    Some((then, at)) if done > then => {
+                        let tick_time = (now - at) / (done - then).try_into().unwrap_or(u32::MAX);
+                        self.policy.ticks_between(tick_time)
+                    }
+                    _ => 1,
+                };
+                self.last = Some((done, now));
+                ticks
+            }
+        };
+        self.next = done.saturating_add(ticks);
+        true
+    }
+}
+
+/// Same as `run_simulation`, but gives up once `budget` of wall-clock time has
+/// passed, e.g. to bound a web request however large the grid is.
+///
+/// Returns the grid reached, the number of ticks completed, and whether the
+/// deadline was hit. The clock is read between ticks on an adaptive
+/// `CheckInterval` aiming for at least ten checks per budget (and no more than
+/// one per `DEFAULT_CHECK_TARGET`), so the grid is always the exact state after
+/// the reported tick count and hashes like `run_simulation(grid, completed)`.
+/// A run that finishes all `steps` reports `false` even if the budget ran out
+/// during the last ticks.
+pub fn run_simulation_timeout(mut grid: Grid<u8>, steps: usize, budget: Duration) -> (Grid<u8>, usize, bool) {
+    let mut checks = CheckInterval::Adaptive { target: DEFAULT_CHECK_TARGET.min(budget / 10) }.schedule();
+    let start = Instant::now();
+
+    for done in 0..steps {
+        if checks.due(done) && start.elapsed() >= budget {
+            return (grid, done, true);
+        }
+        grid = tick(&grid);
+    }
+    (grid, steps, false)
+}
+
+/// Same as `run_simulation`, but also records the population (`count_alive`)
+/// along the way, so convergence can be plotted without rerunning.
+///
+/// Entry `i` is the population after tick `min((i + 1) * sample_every, steps)`:
+/// with `sample_every = 1` there is exactly one entry per tick, and larger
+/// intervals keep huge runs bounded at `ceil(steps / sample_every)` entries.
+/// For `steps > 0` the last entry is always the final population; `steps = 0`
+/// runs no ticks and gives an empty timeline. The grid is identical to
+/// `run_simulation`.
+///
+/// # Panics
+/// If `sample_every` is 0.
+pub fn run_simulation_population(mut grid: Grid<u8>, steps: usize, sample_every: usize) -> (Grid<u8>, Vec<usize>) {
+    assert!(sample_every > 0, "Sampling interval must be non-zero");
+
+    let mut timeline = Vec::with_capacity(steps.div_ceil(sample_every));
+    for done in 1..=steps {
         grid = tick(&grid);
+        if done % sample_every == 0 || done == steps {
+            timeline.push(grid.count_alive());
+        }
+    }
+    (grid, timeline)
+}
+
+/// Throughput of a simulation run, as measured by `run_simulation_metered`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimMetrics {
+    /// Wall-clock time spent ticking.
+    pub elapsed: Duration,
+    /// Cell updates performed: `steps * width * height`.
+    pub cells_updated: u64,
+    /// `cells_updated / elapsed`, or 0 if the run was too fast to measure.
+    pub cells_per_sec: f64,
+}
+
+impl SimMetrics {
+    /// Metrics for `steps` ticks over `cells` cells that took `elapsed`, for
+    /// callers that time a run themselves (e.g. one that stops on extinction).
+    pub fn new(cells: usize, steps: usize, elapsed: Duration) -> Self {
+        let cells_updated = (cells as u64).saturating_mul(steps as u64);
+        let secs = elapsed.as_secs_f64();
+        let cells_per_sec = if secs > 0.0 { cells_updated as f64 / secs } else { 0.0 };
+        SimMetrics { elapsed, cells_updated, cells_per_sec }
+    }
+}
+
+/// Same as `run_simulation`, but also measures throughput. Only the whole run is
+/// timed, so the overhead is a single clock read and the grid (and therefore the
+/// hash) is identical to `run_simulation`.
+pub fn run_simulation_metered(grid: Grid<u8>, steps: usize) -> (Grid<u8>, SimMetrics) {
+    let cells = grid.as_raw().len();
+    let start = Instant::now();
+    let grid = run_simulation(grid, steps);
+    (grid, SimMetrics::new(cells, steps, start.elapsed()))
+}
+
+/// `Rule::INK` as a lookup table, built at compile time.
+const INK_TABLE: RuleTable = Rule::INK.table();
+
+/// Advances the grid by one generation (t -> t+1) under `rule`, on the standard
+/// Moore-neighborhood torus. Useful for interactive steppers that advance one
+/// generation at a time; `run_simulation` is just this in a loop.
+pub fn step(grid: &Grid<u8>, rule: &Rule) -> Grid<u8> {
+    tick_with(grid, rule, Neighborhood::Moore, Boundary::Torus)
+}
+
+/// Advances the grid by one time step (t -> t+1) with the default Ink rule.
+pub fn tick(current: &Grid<u8>) -> Grid<u8> {
+    step(current, &Rule::INK)
+}
+
+/// Runs `steps` ticks with a custom rule, neighborhood and boundary.
+/// With the defaults this is exactly `run_simulation`.
+pub(crate) fn run_simulation_with(
+    mut grid: Grid<u8>,
+    steps: usize,
+    rule: &Rule,
+    neighborhood: Neighborhood,
+    boundary: Boundary,
+) -> Grid<u8> {
+    for _ in 0..steps {
+        grid = tick_with(&grid, rule, neighborhood, boundary);
     }
     grid
 }
 
-/// Advances the grid by one time step (t -> t+1).
-fn tick(current: &Grid<u8>) -> Grid<u8> {
+/// One tick under any rule/neighborhood/boundary combination.
+///
+/// The default Moore + torus geometry takes the fast path: one `tick_row` per
+/// row, which reads interior neighbors straight from row slices and only pays
+/// for wrapping math on the border. The output is identical to a per-cell scan
+/// with `get`.
+/// Any other geometry, or a column-major grid, uses a straightforward per-cell
+/// scan through `get_bounded`.
+fn tick_with(current: &Grid<u8>, rule: &Rule, neighborhood: Neighborhood, boundary: Boundary) -> Grid<u8> {
+    if neighborhood != Neighborhood::Moore || boundary != Boundary::Torus || current.layout() != Layout::RowMajor {
+        return tick_generic(current, rule, neighborhood, boundary);
+    }
+
+    let mut next = Grid::new(current.width(), current.height());
+    tick_into(current, rule, next.as_raw_mut());
+    next
+}
+
+/// The fast Moore + torus tick, written into `out` (same length and layout as
+/// `current`'s cells) so callers can reuse one buffer across generations.
+fn tick_into(current: &Grid<u8>, rule: &Rule, out: &mut [u8]) {
+    let width = current.width();
+    if width == 0 || current.height() == 0 {
+        return;
+    }
+    if current.layout() != Layout::RowMajor {
+        // The rows below are rows of the raw buffer; take the per-cell path.
+        let next = tick_generic(current, rule, Neighborhood::Moore, Boundary::Torus);
+        out.copy_from_slice(next.as_raw());
+        return;
+    }
+
+    let table = rule.table();
+    for (y, out_row) in out.chunks_mut(width).enumerate() {
+        tick_row(current, y, out_row, &table);
+    }
+}
+
+/// Lazily yields successive generations of a grid (see `generations`).
+///
+/// Double-buffered: each tick writes into a buffer owned by the iterator, so the
+/// only allocation per generation is the clone handed to the caller.
+#[derive(Clone, Debug)]
+pub struct Generations {
+    current: Grid<u8>,
+    next: Grid<u8>,
+    rule: Rule,
+}
+
+/// Iterates over the generations after `initial` under `rule` (Moore + torus,
+/// like `step`). The first item is one tick in, and the iterator never ends, so
+/// `generations(g, rule).nth(n)` equals `n + 1` ticks; bound it with `take`.
+///
+/// Warning: every item is a fresh full-size grid, so collecting this iterator
+/// without a bound allocates until memory runs out. Tooling that gathers
+/// generations should use `bounded_generations`.
+pub fn generations(initial: Grid<u8>, rule: Rule) -> Generations {
+    let next = Grid::with_layout(initial.width(), initial.height(), initial.layout());
+    Generations {
+        current: initial,
+        next,
+        rule,
+    }
+}
+
+/// `generations` capped at `max` items: the grids after 1, 2, ..., `max` ticks,
+/// then `None`. Collecting it holds at most `max` grids.
+pub fn bounded_generations(initial: Grid<u8>, rule: Rule, max: usize) -> std::iter::Take<Generations> {
+    generations(initial, rule).take(max)
+}
+
+impl Iterator for Generations {
+    type Item = Grid<u8>;
+
+    fn next(&mut self) -> Option<Grid<u8>> {
+        tick_into(&self.current, &self.rule, self.next.as_raw_mut());
+        std::mem::swap(&mut self.current, &mut self.next);
+        Some(self.current.clone())
+    }
+}
+
+/// Computes row `y` of the next generation into `out_row` (length = width).
+///
+/// The work is split in two so the hot loop never pays for wrapping math:
+/// 1. Interior cells (x and y strictly inside the border) have all 8 neighbors in
+///    range, so they're read directly from row slices (`y * width + x`).
+/// 2. Border cells (the outer ring) wrap around the torus, so they take the
+///    generic `count_neighbors` path built on the wrapping `get`.
+///
+/// Either way the rule is a `RuleTable` lookup, built once per tick.
+fn tick_row(current: &Grid<u8>, y: usize, out_row: &mut [u8], rule: &RuleTable) {
+    let width = current.width();
+    let height = current.height();
+    let cells = current.as_raw();
+    let row = &cells[y * width..(y + 1) * width];
+
+    let update_wrapped = |x: usize, out_row: &mut [u8]| {
+        let neighbors = count_neighbors(current, x as isize, y as isize);
+        out_row[x] = rule.apply(row[x], neighbors);
+    };
+
+    // Top and bottom rows are all border.
+    if y == 0 || y + 1 == height {
+        for x in 0..width {
+            update_wrapped(x, out_row);
+        }
+        return;
+    }
+
+    // 1. Interior
+    let above = &cells[(y - 1) * width..y * width];
+    let below = &cells[(y + 1) * width..(y + 2) * width];
+    for x in 1..width.saturating_sub(1) {
+        let neighbors = is_ink(above[x - 1]) + is_ink(above[x]) + is_ink(above[x + 1])
+            + is_ink(row[x - 1]) + is_ink(row[x + 1])
+            + is_ink(below[x - 1]) + is_ink(below[x]) + is_ink(below[x + 1]);
+        out_row[x] = rule.apply(row[x], neighbors);
+    }
+
+    // 2. Border columns (on a 1-wide grid both are the same cell, which is harmless).
+    update_wrapped(0, out_row);
+    update_wrapped(width - 1, out_row);
+}
+
+/// Returns 1 if the cell counts as an "Ink" neighbor, 0 otherwise.
+#[inline(always)]
+fn is_ink(cell: u8) -> u8 {
+    (cell == 1) as u8
+}
+
+/// Per-cell tick for non-default geometry (any neighborhood, any boundary).
+fn tick_generic(current: &Grid<u8>, rule: &Rule, neighborhood: Neighborhood, boundary: Boundary) -> Grid<u8> {
     let width = current.width();
     let height = current.height();
-    let mut next = Grid::new(width, height);
+    let mut next = Grid::with_layout(width, height, current.layout());
 
-    // Iterate over every cell
-    // Note: This loops is O(N^2). In Rust --release, this gets auto-vectorized by LLVM.
     for y in 0..height {
         for x in 0..width {
-            let neighbors = count_neighbors(current, x as isize, y as isize);
+            let neighbors = count_neighbors_with(current, x as isize, y as isize, neighborhood, boundary);
             let state = current.get(x as isize, y as isize);
-            
-            // Apply the "Ink" Rule
-            // Current Rule: Similar to Conway's Game of Life but adjusted for chaos.
-            // 1 = Ink, 0 = Water
-            let new_state = match (state, neighbors) {
-                (1, 2) | (1, 3) => 1, // Survival: Ink stays if it has 2 or 3 neighbors
-                (0, 3) => 1,          // Birth: Ink spreads to empty spots with 3 neighbors
-                _ => 0,               // Death: Overcrowding (>3) or Loneliness (<2)
-            };
-            
-            next.set(x, y, new_state);
+            next.set(x, y, rule.apply(state, neighbors));
+        }
+    }
+    next
+}
+
+/// One tick (on the torus) where the neighbor count is the weighted sum over
+/// `neighborhood`: each ink neighbor adds its weight. The sum is checked
+/// against the rule's birth/survival counts as usual, and a sum above 8
+/// matches none of them. All weights 1 over the Moore offsets is exactly `step`.
+pub fn tick_weighted(current: &Grid<u8>, rule: &Rule, neighborhood: &WeightedNeighborhood) -> Grid<u8> {
+    let width = current.width();
+    let height = current.height();
+    let mut next = Grid::with_layout(width, height, current.layout());
+
+    for y in 0..height {
+        for x in 0..width {
+            let (x, y) = (x as isize, y as isize);
+            let sum: u32 = neighborhood
+                .offsets
+                .iter()
+                .filter(|((dx, dy), _)| current.get(x + dx, y + dy) == 1)
+                .map(|&(_, weight)| weight as u32)
+                .sum();
+            // `Rule` counts stop at 8, so clamping to 9 keeps "too many" a non-match.
+            let neighbors = sum.min(9) as u8;
+            next.set(x as usize, y as usize, rule.apply(current.get(x, y), neighbors));
         }
     }
     next
@@ -112,6 +1118,8 @@ fn tick(current: &Grid<u8>) -> Grid<u8> {
 
 /// Counts the number of active ("Ink") neighbors around a coordinate.
 /// Uses the Grid's internal wrapping (torus) logic automatically.
+/// `tick` only takes this path for border cells; interior cells read their
+/// neighbors directly.
 /// 
 /// Neighborhood: Moore (8 surrounding cells)
 /// [ ][ ][ ]
@@ -119,16 +1127,8 @@ fn tick(current: &Grid<u8>) -> Grid<u8> {
 /// [ ][ ][ ]
 fn count_neighbors(grid: &Grid<u8>, x: isize, y: isize) -> u8 {
     let mut count = 0;
-    
-    // Check all 8 directions
-    // We use an array of offsets to avoid writing 8 if-statements.
-    let offsets = [
-        (-1, -1), (0, -1), (1, -1),
-        (-1,  0),          (1,  0),
-        (-1,  1), (0,  1), (1,  1)
-    ];
-
-    for (dx, dy) in offsets.iter() {
+
+    for (dx, dy) in MOORE_OFFSETS.iter() {
         // If the neighbor is 1 (Ink), add to count
         if grid.get(x + dx, y + dy) == 1 {
             count += 1;
@@ -137,3 +1137,1329 @@ fn count_neighbors(grid: &Grid<u8>, x: isize, y: isize) -> u8 {
     
     count
 }
+
+/// Like `count_neighbors`, for any neighborhood and boundary.
+fn count_neighbors_with(grid: &Grid<u8>, x: isize, y: isize, neighborhood: Neighborhood, boundary: Boundary) -> u8 {
+    neighborhood
+        .offsets()
+        .iter()
+        .filter(|(dx, dy)| grid.get_bounded(x + dx, y + dy, boundary) == 1)
+        .count() as u8
+}
+
+impl Grid<u8> {
+    /// Fills `out` with the live-neighbor count of every cell (in row-major
+    /// order whatever the layout, on the torus), so counting can be separated
+    /// from applying a rule.
+    ///
+    /// `out` is cleared and refilled to exactly `width * height` entries; its
+    /// allocation is reused, so calling this every tick doesn't reallocate.
+    pub fn neighbor_counts_into(&self, out: &mut Vec<u8>, neighborhood: Neighborhood) {
+        out.clear();
+        out.reserve(self.as_raw().len());
+        for y in 0..self.height() as isize {
+            for x in 0..self.width() as isize {
+                out.push(count_neighbors_with(self, x, y, neighborhood, Boundary::Torus));
+            }
+        }
+    }
+
+    /// How many cells have 0, 1, ..., 8 live neighbors (index = neighbor count).
+    /// The buckets sum to `width * height`; von Neumann counts only reach index 4.
+    pub fn neighbor_histogram(&self, neighborhood: Neighborhood) -> [usize; 9] {
+        let mut counts = Vec::new();
+        self.neighbor_counts_into(&mut counts, neighborhood);
+
+        let mut histogram = [0; 9];
+        for count in counts {
+            histogram[count as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Weighted sum over a stencil at every cell, on the torus: cell (x, y) of
+    /// the result is the sum of `weight * cell(x + dx, y + dy)` over each
+    /// `(dx, dy, weight)` in `stencil`, using the cell values as they are (not
+    /// just 0/1). The result keeps this grid's layout; sums wrap on `i32` overflow.
+    ///
+    /// `[(0, 0, 1)]` copies the grid, the eight Moore offsets with weight 1 give
+    /// neighbor counts, and a box of `1`s around `(0, 0, 1)` is an unnormalized blur.
+    pub fn convolve(&self, stencil: &[(isize, isize, i32)]) -> Grid<i32> {
+        let mut out = Grid::with_layout(self.width(), self.height(), self.layout());
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let sum = stencil.iter().fold(0i32, |sum, &(dx, dy, weight)| {
+                    let cell = self.get(x as isize + dx, y as isize + dy);
+                    sum.wrapping_add(weight.wrapping_mul(i32::from(cell)))
+                });
+                out.set(x, y, sum);
+            }
+        }
+        out
+    }
+}
+
+// --- Part 4: Calibration ---
+
+/// Number of ticks timed by `calibrate_steps` before extrapolating.
+const CALIBRATION_TICKS: usize = 8;
+
+/// Estimates how many steps a `width x height` simulation can run within `target`
+/// on the current machine.
+///
+/// We time a few ticks on a representative (seeded) grid and extrapolate linearly.
+/// The result is advisory only: it depends on the hardware and is NOT part of the
+/// proof, so the nondeterminism here is fine. Always returns at least 1.
+pub fn calibrate_steps(width: usize, height: usize, target: Duration) -> usize {
+    let grid = generate_grid_from_seed("calibration", "calibration", width, height);
+
+    let start = Instant::now();
+    let _ = run_simulation(grid, CALIBRATION_TICKS);
+    let elapsed = start.elapsed();
+
+    steps_within(target, elapsed.as_nanos() / CALIBRATION_TICKS as u128)
+}
+
+/// Steps of `per_tick` nanoseconds each that fit in `target`, at least 1.
+fn steps_within(target: Duration, per_tick: u128) -> usize {
+    // Guard against a zero reading on tiny grids / coarse clocks.
+    let steps = target.as_nanos() / per_tick.max(1);
+    steps.clamp(1, usize::MAX as u128) as usize
+}
+
+/// Largest side `estimate_runtime` actually simulates; bigger grids are extrapolated.
+const ESTIMATE_SAMPLE_SIDE: usize = 256;
+
+/// Estimates how long `steps` ticks of a `width x height` simulation will take
+/// on the current machine, without allocating the full grid.
+///
+/// Times a few ticks on a sample of at most 256x256 cells and scales the cost
+/// per cell linearly (tick cost is linear in the cell count). Advisory only, like
+/// `calibrate_steps`; saturates at `Duration::MAX`.
+pub fn estimate_runtime(width: usize, height: usize, steps: usize) -> Duration {
+    let sample_width = width.clamp(1, ESTIMATE_SAMPLE_SIDE);
+    let sample_height = height.clamp(1, ESTIMATE_SAMPLE_SIDE);
+    let grid = generate_grid_from_seed("calibration", "calibration", sample_width, sample_height);
+
+    let start = Instant::now();
+    let _ = run_simulation(grid, CALIBRATION_TICKS);
+    let elapsed = start.elapsed();
+
+    let sample_cell_ticks = (CALIBRATION_TICKS * sample_width * sample_height) as f64;
+    let cell_ticks = width as f64 * height as f64 * steps as f64;
+    Duration::try_from_secs_f64(elapsed.as_secs_f64() / sample_cell_ticks * cell_ticks).unwrap_or(Duration::MAX)
+}
+
+/// How each grid buffer stores its cells, for `estimate_footprint`.
+///
+/// Only `Bytes` for now: `PackedGrid` can hold a grid but nothing ticks one
+/// yet, so there is no packed run whose footprint could be estimated (or
+/// checked against real allocations). A `Packed` variant comes with one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StorageKind {
+    /// `Grid<u8>`, one byte per cell: what every simulation path ticks.
+    #[default]
+    Bytes,
+}
+
+/// Peak bytes of cell storage for a proof over a `width x height` grid with
+/// `lanes` lanes (see `SimulationBuilder::lanes`), so a caller can refuse a
+/// request before `prove_work` allocates anything.
+///
+/// The simulation double-buffers, so two grids are alive at the peak. Lanes
+/// are proved one after another, each dropping its grids before the next
+/// starts, so they add only their 32-byte digests. Allocator bookkeeping, the
+/// few fixed-size values of a run and the one-time thread pool of `parallel`
+/// builds are not counted.
+///
+/// Every product is checked: a size whose footprint overflows `usize`, or
+/// whose cell count exceeds `MAX_CELLS`, is `GridError::TooLarge`, never a
+/// wrapped-around small number.
+///
+/// Panics if `lanes` is 0, like `SimulationBuilder::lanes`.
+pub fn estimate_footprint(width: usize, height: usize, lanes: usize, storage: StorageKind) -> Result<usize, GridError> {
+    assert!(lanes > 0, "A proof needs at least one lane");
+    let too_large = GridError::TooLarge { width, height };
+
+    let grid_bytes = match storage {
+        StorageKind::Bytes => cell_count(width, height)?,
+    };
+    grid_bytes
+        .checked_mul(2)
+        .and_then(|grids| grids.checked_add(lanes.checked_mul(32)?))
+        .ok_or(too_large)
+}
+
+/// Highest level accepted by `params_for_difficulty` (a 262144 x 262144 grid).
+pub const MAX_DIFFICULTY_LEVEL: u32 = 24;
+
+/// Maps a difficulty level onto `(width, height, steps)`, so client and server
+/// agree on the policy instead of passing dimensions around.
+///
+/// Schedule (deterministic, unlike `calibrate_steps`):
+/// - Square grids. The side starts at 64 and grows geometrically: it doubles
+///   every two levels, with odd levels at 1.5x the level below
+///   (64, 96, 128, 192, 256, ...), so the cell count roughly doubles per level.
+/// - `steps = 100 * (level + 1)`.
+///
+/// Every level therefore costs strictly more memory and more steps than the one
+/// below. Panics if `level > MAX_DIFFICULTY_LEVEL`.
+pub fn params_for_difficulty(level: u32) -> (usize, usize, usize) {
+    assert!(
+        level <= MAX_DIFFICULTY_LEVEL,
+        "Difficulty level must be at most {}",
+        MAX_DIFFICULTY_LEVEL
+    );
+
+    let base = 64usize << (level / 2);
+    let side = if level % 2 == 1 { base * 3 / 2 } else { base };
+    let steps = 100 * (level as usize + 1);
+    (side, side, steps)
+}
+
+/// Side of the fixed grid `benchmark_probe` simulates.
+pub const BENCHMARK_SIDE: usize = 128;
+
+/// Ticks `benchmark_probe` runs (~262k cell updates, a few ms on a phone).
+pub const BENCHMARK_TICKS: usize = 16;
+
+/// Proving time `recommended_params` aims for, in milliseconds.
+pub const RECOMMENDED_TARGET_MS: f64 = 1000.0;
+
+/// Highest difficulty level `recommended_params` picks (a 4096 x 4096 grid,
+/// 32 MiB for the double buffer), whatever the benchmark says.
+pub const MAX_RECOMMENDED_LEVEL: u32 = 12;
+
+/// A fixed workload for the caller to time: `BENCHMARK_TICKS` ticks of a
+/// `BENCHMARK_SIDE`-square seeded grid. Returns the final population only so
+/// the work cannot be optimized away.
+///
+/// Takes no clock readings itself, so it works where `Instant` does not (the
+/// browser times it with `performance.now()`).
+pub fn benchmark_probe() -> usize {
+    let grid = generate_grid_from_seed("benchmark", "benchmark", BENCHMARK_SIDE, BENCHMARK_SIDE);
+    std::hint::black_box(run_simulation(grid, BENCHMARK_TICKS)).count_alive()
+}
+
+/// Picks `(width, height, steps)` for a proof taking about
+/// `RECOMMENDED_TARGET_MS` on a device where `benchmark_probe` took
+/// `benchmark_ms`.
+///
+/// The probe time gives a cost per cell update, and the result is the highest
+/// `params_for_difficulty` level (at most `MAX_RECOMMENDED_LEVEL`) whose
+/// `width * height * steps` fits the target. A pure function of its input:
+/// a faster probe never yields a smaller level. A zero probe time (coarse
+/// timers) gets the top level; a negative or NaN one gets level 0.
+pub fn recommended_params(benchmark_ms: f64) -> (usize, usize, usize) {
+    let probe_cell_ticks = (BENCHMARK_SIDE * BENCHMARK_SIDE * BENCHMARK_TICKS) as f64;
+    let budget = if benchmark_ms >= 0.0 {
+        RECOMMENDED_TARGET_MS / benchmark_ms * probe_cell_ticks
+    } else {
+        0.0
+    };
+
+    let level = (1..=MAX_RECOMMENDED_LEVEL)
+        .take_while(|&level| {
+            let (width, height, steps) = params_for_difficulty(level);
+            (width * height * steps) as f64 <= budget
+        })
+        .last()
+        .unwrap_or(0);
+    params_for_difficulty(level)
+}
+
+// --- Part 5: Analysis (not used by proofs) ---
+
+/// Detects whether the simulation settles into a cycle within `max_steps` ticks.
+///
+/// Returns the cycle length once a state repeats (1 = still life, 2 = blinker, ...),
+/// or `None` if no state repeats. We remember a SHA-256 of every visited state
+/// instead of the grids themselves, so memory stays at 32 bytes per step.
+pub fn detect_period(grid: &Grid<u8>, max_steps: usize) -> Option<usize> {
+    let mut seen: HashMap<[u8; 32], usize> = HashMap::new();
+    let mut current = grid.clone();
+
+    for step in 0..=max_steps {
+        let digest: [u8; 32] = Sha256::digest(current.as_raw()).into();
+        if let Some(first_seen) = seen.insert(digest, step) {
+            return Some(step - first_seen);
+        }
+        if step < max_steps {
+            current = tick(&current);
+        }
+    }
+    None
+}
+
+impl Grid<u8> {
+    /// Labels the connected blobs of Ink (non-zero cells) on the torus.
+    ///
+    /// Returns a label grid (0 = dead, 1..=k = component, numbered in row-major
+    /// order of each blob's first cell) and the component count `k`. Adjacency
+    /// follows `neighborhood` and wraps, so blobs touching across an edge are one
+    /// component.
+    pub fn connected_components(&self, neighborhood: Neighborhood) -> (Grid<u32>, usize) {
+        let width = self.width();
+        let height = self.height();
+        let grid = self.row_major();
+        let cells = grid.as_raw();
+        let mut labels: Grid<u32> = Grid::new(width, height);
+        let mut count = 0;
+        let mut stack = Vec::new();
+
+        for start in 0..cells.len() {
+            if cells[start] == 0 || labels.as_raw()[start] != 0 {
+                continue;
+            }
+
+            count += 1;
+            let label = count as u32;
+            labels.as_raw_mut()[start] = label;
+            stack.push(start);
+            while let Some(index) = stack.pop() {
+                let (x, y) = ((index % width) as isize, (index / width) as isize);
+                for (dx, dy) in neighborhood.offsets() {
+                    let nx = (x + dx).rem_euclid(width as isize) as usize;
+                    let ny = (y + dy).rem_euclid(height as isize) as usize;
+                    let neighbor = ny * width + nx;
+                    if cells[neighbor] != 0 && labels.as_raw()[neighbor] == 0 {
+                        labels.as_raw_mut()[neighbor] = label;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        (labels.into_layout(self.layout()), count)
+    }
+}
+
+// --- Part 6: Difficulty Target (Nonce Search) ---
+
+/// Counts the leading zero bits of a digest (the "difficulty" it satisfies).
+pub fn leading_zero_bits(digest: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in digest {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Searches for the first nonce at or above `start_nonce` whose final grid hash has
+/// at least `difficulty_bits` leading zero bits.
+///
+/// Returns `(nonce, hex_hash)`, or `None` if the nonce space is exhausted.
+/// Each attempt seeds a fresh grid via `generate_grid_with_nonce` and runs the full
+/// simulation. Starting at a later nonce lets a retry resume where it stopped;
+/// `search_nonce_range` bounds the search for coordinator/worker sharding.
+pub fn prove_with_target(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    steps: usize,
+    difficulty_bits: u32,
+    start_nonce: u64,
+) -> Option<(u64, String)> {
+    search_nonce_range(username, password, width, height, steps, difficulty_bits, start_nonce..=u64::MAX)
+}
+
+/// Like `prove_with_target`, but only tries nonces in `nonces` (`[start, end]`,
+/// so a shard can reach `u64::MAX`); a coordinator hands disjoint shards to workers.
+pub fn search_nonce_range(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    steps: usize,
+    difficulty_bits: u32,
+    nonces: RangeInclusive<u64>,
+) -> Option<(u64, String)> {
+    for nonce in nonces {
+        let grid = generate_grid_with_nonce(username, password, nonce, width, height);
+        let digest = HashAlgo::Sha256.digest(&run_simulation(grid, steps), &[]);
+        if leading_zero_bits(&digest) >= difficulty_bits {
+            return Some((nonce, hex::encode(digest)));
+        }
+    }
+    None
+}
+
+// --- Part 7: Grid Hashes ---
+
+/// The proof hash: lowercase hex SHA-256 of the grid's raw cells (row-major, one
+/// byte per cell). The plain, nonce-search, builder and challenge proofs all
+/// hash through `HashAlgo::digest`, which this wraps.
+pub fn hash_grid(grid: &Grid<u8>) -> String {
+    hash_grid_with_context(grid, &[])
+}
+
+/// Same as `hash_grid`, but feeds `context` into SHA-256 after the grid bytes.
+///
+/// Binds a proof to one challenge or session: the same credentials give a
+/// different hash per context. An empty context reproduces `hash_grid` exactly.
+pub fn hash_grid_with_context(grid: &Grid<u8>, context: &[u8]) -> String {
+    HashAlgo::Sha256.hash_grid(grid, context)
+}
+
+/// Shortest digest `HashAlgo::Sha256Trunc` may produce, in bytes. Anything
+/// shorter is cheap enough to brute-force that the proof stops meaning much.
+pub const MIN_TRUNCATED_BYTES: usize = 8;
+
+/// Which digest a proof uses over the final grid (and context).
+///
+/// `Sha256` is the original and default. Selected algorithms are recorded in the
+/// proof parameters, so the verifier always re-hashes with the same one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HashAlgo {
+    /// SHA-256, 32 bytes (64 hex characters).
+    #[default]
+    Sha256,
+    /// SHA-512, 64 bytes (128 hex characters).
+    Sha512,
+    /// The first `n` bytes of SHA-256, with `MIN_TRUNCATED_BYTES <= n <= 32`.
+    Sha256Trunc(usize),
+}
+
+impl HashAlgo {
+    /// Digest length in bytes.
+    pub fn output_len(&self) -> usize {
+        match self {
+            HashAlgo::Sha256 => 32,
+            HashAlgo::Sha512 => 64,
+            HashAlgo::Sha256Trunc(len) => *len,
+        }
+    }
+
+    /// False for truncation lengths outside `MIN_TRUNCATED_BYTES..=32`.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            HashAlgo::Sha256Trunc(len) => (MIN_TRUNCATED_BYTES..=32).contains(len),
+            _ => true,
+        }
+    }
+
+    /// Digest of the grid's cells in row-major order followed by `context`, so
+    /// the same logical grid hashes the same in either layout.
+    /// Panics if the algorithm is not `is_valid`.
+    pub fn digest(&self, grid: &Grid<u8>, context: &[u8]) -> Vec<u8> {
+        assert!(self.is_valid(), "Truncation must be {}-32 bytes", MIN_TRUNCATED_BYTES);
+        let grid = grid.row_major();
+        match self {
+            HashAlgo::Sha512 => {
+                let mut hasher = Sha512::new();
+                hasher.update(grid.as_raw());
+                hasher.update(context);
+                hasher.finalize().to_vec()
+            }
+            HashAlgo::Sha256 | HashAlgo::Sha256Trunc(_) => {
+                let mut hasher = Sha256::new();
+                hasher.update(grid.as_raw());
+                hasher.update(context);
+                hasher.finalize()[..self.output_len()].to_vec()
+            }
+        }
+    }
+
+    /// `digest(&run_simulation(grid, steps), context)` without materializing the
+    /// final grid: the first `steps - 1` ticks run double-buffered (see
+    /// `run_simulation_double_buffered`), and the last tick computes one row at
+    /// a time into a row buffer that goes straight into the hasher. Memory peaks
+    /// at two grid buffers. Panics if the algorithm is not `is_valid`.
+    pub fn digest_streaming(&self, grid: Grid<u8>, steps: usize, context: &[u8]) -> Vec<u8> {
+        assert!(self.is_valid(), "Truncation must be {}-32 bytes", MIN_TRUNCATED_BYTES);
+        match self {
+            HashAlgo::Sha512 => hash_last_tick(Sha512::new(), grid, steps, context).to_vec(),
+            HashAlgo::Sha256 | HashAlgo::Sha256Trunc(_) => {
+                hash_last_tick(Sha256::new(), grid, steps, context)[..self.output_len()].to_vec()
+            }
+        }
+    }
+
+    /// Lowercase hex of `digest` (what proofs carry).
+    pub fn hash_grid(&self, grid: &Grid<u8>, context: &[u8]) -> String {
+        hex::encode(self.digest(grid, context))
+    }
+}
+
+/// The body of `HashAlgo::digest_streaming` for one hasher type.
+fn hash_last_tick<D: Digest>(mut hasher: D, grid: Grid<u8>, steps: usize, context: &[u8]) -> sha2::digest::Output<D> {
+    if steps == 0 || grid.layout() != Layout::RowMajor {
+        // Nothing to stream, or `tick_row` can't walk the storage: hash the full run.
+        hasher.update(run_simulation_double_buffered(grid, steps).row_major().as_raw());
+    } else {
+        let before_last = run_simulation_double_buffered(grid, steps - 1);
+        let mut row = vec![0u8; before_last.width()];
+        if !row.is_empty() {
+            for y in 0..before_last.height() {
+                tick_row(&before_last, y, &mut row, &INK_TABLE);
+                hasher.update(&row);
+            }
+        }
+    }
+    hasher.update(context);
+    hasher.finalize()
+}
+
+/// Names used in proof strings and on the command line:
+/// `sha256`, `sha512` and `sha256t{n}` (e.g. `sha256t16`).
+impl fmt::Display for HashAlgo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgo::Sha256 => write!(f, "sha256"),
+            HashAlgo::Sha512 => write!(f, "sha512"),
+            HashAlgo::Sha256Trunc(len) => write!(f, "sha256t{}", len),
+        }
+    }
+}
+
+impl std::str::FromStr for HashAlgo {
+    type Err = String;
+
+    /// Parses the names written by `Display`, rejecting invalid truncations.
+    fn from_str(text: &str) -> Result<Self, String> {
+        let algo = match text {
+            "sha256" => HashAlgo::Sha256,
+            "sha512" => HashAlgo::Sha512,
+            _ => {
+                let len = text
+                    .strip_prefix("sha256t")
+                    .and_then(|len| len.parse().ok())
+                    .ok_or_else(|| format!("Unknown hash algorithm '{}'", text))?;
+                HashAlgo::Sha256Trunc(len)
+            }
+        };
+        if !algo.is_valid() {
+            return Err(format!("Truncated SHA-256 must keep {}-32 bytes, got '{}'", MIN_TRUNCATED_BYTES, text));
+        }
+        Ok(algo)
+    }
+}
+
+/// Hashes the grid by feeding its cells to SHA-256 in `chunk`-sized pieces.
+/// The digest is identical to `hash_grid`; `chunk` only bounds how much is
+/// handed to the hasher per call. Returns the hex string.
+pub fn hash_grid_streaming(grid: &Grid<u8>, chunk: usize) -> String {
+    assert!(chunk > 0, "Chunk size must be non-zero");
+
+    let grid = grid.row_major();
+    let mut hasher = Sha256::new();
+    for piece in grid.as_raw().chunks(chunk) {
+        hasher.update(piece);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Runs `steps` ticks and returns the hex SHA-256 of the final grid, without ever
+/// materializing the final grid.
+///
+/// The first `steps - 1` ticks run as usual; the last tick computes one row at a
+/// time into a single row buffer and streams it straight into the hasher (see
+/// `HashAlgo::digest_streaming`). The digest equals
+/// `hash_grid(&run_simulation(grid, steps))`.
+pub fn simulate_and_hash_streaming(grid: Grid<u8>, steps: usize) -> String {
+    hex::encode(HashAlgo::Sha256.digest_streaming(grid, steps, &[]))
+}
+
+/// Runs `steps` ticks and returns a hex SHA-256 that binds the whole
+/// trajectory, not just the final grid, at a granularity of `batch` steps.
+///
+/// One hasher runs for the whole run. It absorbs the step index (u64,
+/// little-endian) followed by the grid bytes for step 0, every multiple of
+/// `batch`, and the final step if it is not one already. `batch = 1` binds every
+/// tick; `batch >= steps` binds only the seed and the final grid, with the step
+/// count mixed in. Different batch sizes give different hashes, so verifiers
+/// must use the prover's.
+///
+/// # Panics
+/// If `batch` is 0.
+pub fn run_path_bound(grid: Grid<u8>, steps: usize, batch: usize) -> String {
+    assert!(batch > 0, "Batch size must be non-zero");
+
+    let mut hasher = Sha256::new();
+    let mut absorb = |step: usize, grid: &Grid<u8>| {
+        hasher.update((step as u64).to_le_bytes());
+        hasher.update(grid.row_major().as_raw());
+    };
+
+    let mut grid = grid;
+    absorb(0, &grid);
+    let mut done = 0;
+    while done < steps {
+        let run = batch.min(steps - done);
+        grid = run_simulation(grid, run);
+        done += run;
+        absorb(done, &grid);
+    }
+    hex::encode(hasher.finalize())
+}
+
+// --- Part 8: Hash-Chained Sequential Mode (VDF) ---
+
+/// Cells flipped per tick by `run_vdf`: enough that every tick's trajectory
+/// depends on the previous hash, few enough not to drown the Life dynamics.
+pub const VDF_PERTURBED_CELLS: usize = 4;
+
+/// Strictly sequential variant of the standard proof, for use as a delay function.
+///
+/// Before every tick the SHA-256 of the current grid seeds a `DetRng` that picks
+/// `VDF_PERTURBED_CELLS` cells to flip (Ink <-> Water). Tick N therefore cannot
+/// start before the hash of tick N-1's output is known: no skipping ahead, and
+/// no spreading the ticks of one proof across machines. Each tick costs one
+/// extra full-grid hash.
+///
+/// Returns the hex SHA-256 of the final grid. It is a different proof from
+/// `run_simulation` with the same inputs; a verifier simply re-runs `run_vdf`.
+pub fn run_vdf(username: &str, password: &str, width: usize, height: usize, steps: usize) -> String {
+    let mut grid = generate_grid_from_seed(username, password, width, height);
+    let cells = grid.as_raw().len();
+
+    for _ in 0..steps {
+        if cells > 0 {
+            let mut rng = DetRng::from_bytes(&Sha256::digest(grid.as_raw()));
+            for _ in 0..VDF_PERTURBED_CELLS {
+                let index = (rng.next_u64() % cells as u64) as usize;
+                let cell = &mut grid.as_raw_mut()[index];
+                *cell = is_ink(*cell) ^ 1;
+            }
+        }
+        grid = tick(&grid);
+    }
+    hash_grid(&grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The textbook B3/S23 torus tick, one cell at a time with explicit wrapping
+    /// math: the spec the optimized paths must match.
+    fn naive_tick(grid: &Grid<u8>) -> Grid<u8> {
+        let (width, height) = (grid.width() as isize, grid.height() as isize);
+        let cell = |x: isize, y: isize| grid.as_raw()[(y.rem_euclid(height) * width + x.rem_euclid(width)) as usize];
+        let mut next = Grid::new(grid.width(), grid.height());
+        for y in 0..height {
+            for x in 0..width {
+                let neighbors = naive_neighbors(grid, x, y);
+                let alive = matches!((cell(x, y), neighbors), (1, 2) | (_, 3));
+                next.set(x as usize, y as usize, u8::from(alive));
+            }
+        }
+        next
+    }
+
+    /// Live Moore neighbors of (x, y) on the torus, by explicit wrapping.
+    fn naive_neighbors(grid: &Grid<u8>, x: isize, y: isize) -> u8 {
+        let (width, height) = (grid.width() as isize, grid.height() as isize);
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height));
+                if (dx, dy) != (0, 0) && grid.as_raw()[(ny * width + nx) as usize] == 1 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Seeded grids with and without an interior, including degenerate shapes.
+    fn test_grids() -> Vec<Grid<u8>> {
+        [(37, 41), (20, 17), (16, 16), (1, 1), (1, 9), (9, 1), (2, 3)]
+            .iter()
+            .map(|&(width, height)| generate_grid_from_seed("strips", "tick", width, height))
+            .collect()
+    }
+
+    #[test]
+    fn fast_tick_matches_the_naive_tick() {
+        for grid in test_grids() {
+            let (mut fast, mut naive) = (grid.clone(), grid);
+            for _ in 0..5 {
+                fast = tick(&fast);
+                naive = naive_tick(&naive);
+                assert_eq!(fast, naive, "{}x{}", fast.width(), fast.height());
+            }
+        }
+    }
+
+    #[test]
+    fn calibration_is_positive_and_grows_with_the_target() {
+        let targets = [0, 1, 999, 1_000, 50_000, 1_000_000, 10_000_000_000].map(Duration::from_nanos);
+        for per_tick in [0, 1, 700, 1_000_000] {
+            let steps: Vec<usize> = targets.iter().map(|&target| steps_within(target, per_tick)).collect();
+            assert!(steps.iter().all(|&count| count >= 1));
+            assert!(steps.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", steps);
+        }
+        assert_eq!(steps_within(Duration::from_micros(10), 1_000), 10);
+
+        // Real measurements: targets far enough apart that timer noise cannot reorder them.
+        let short = calibrate_steps(32, 32, Duration::from_micros(100));
+        let long = calibrate_steps(32, 32, Duration::from_secs(10));
+        assert!(short >= 1 && long > short);
+    }
+
+    #[test]
+    fn hashes_ignore_layout() {
+        let grid = generate_grid_from_seed("alice", "secret", 13, 7);
+        let col_major = grid.clone().into_layout(Layout::ColMajor);
+        assert_eq!(hash_grid(&col_major), hash_grid(&grid));
+        assert_eq!(hash_grid_streaming(&col_major, 5), hash_grid(&grid));
+        assert_eq!(run_path_bound(col_major.clone(), 3, 1), run_path_bound(grid.clone(), 3, 1));
+        assert_eq!(simulate_and_hash_streaming(col_major, 3), simulate_and_hash_streaming(grid, 3));
+    }
+
+    #[test]
+    fn oversized_seeding_is_an_error() {
+        let too_large = Err(GridError::TooLarge { width: usize::MAX, height: 2 });
+        assert_eq!(try_generate_grid_from_seed("a", "b", usize::MAX, 2), too_large);
+        assert_eq!(try_generate_grid_with_nonce("a", "b", 7, usize::MAX, 2), too_large);
+        assert_eq!(try_generate_grid_droplets("a", "b", usize::MAX, 2, 3), too_large);
+        assert_eq!(try_generate_grid_gradient("a", "b", usize::MAX, 2, 0, 255), too_large);
+        assert_eq!(try_generate_grid_with_endian("a", "b", usize::MAX, 2, SeedEndian::Little), too_large);
+        assert_eq!(try_generate_grid_from_seed("a", "b", 4, 3), Ok(generate_grid_from_seed("a", "b", 4, 3)));
+    }
+
+    #[test]
+    fn population_timeline_matches_the_run() {
+        let seed = generate_grid_from_seed("alice", "secret", 24, 24);
+        let (grid, timeline) = run_simulation_population(seed.clone(), 37, 1);
+        assert_eq!(timeline.len(), 37);
+        assert_eq!(timeline.last(), Some(&grid.count_alive()));
+        assert_eq!(grid, run_simulation(seed.clone(), 37));
+
+        assert_eq!(run_simulation_population(seed.clone(), 37, 10).1.len(), 4);
+        assert!(run_simulation_population(seed, 0, 1).1.is_empty());
+    }
+
+    #[test]
+    fn nonce_search_reaches_u64_max() {
+        let (nonce, hash) = prove_with_target("alice", "secret", 8, 8, 2, 0, u64::MAX).unwrap();
+        assert_eq!(nonce, u64::MAX);
+        let grid = generate_grid_with_nonce("alice", "secret", u64::MAX, 8, 8);
+        assert_eq!(hash, hash_grid(&run_simulation(grid, 2)));
+    }
+
+    #[test]
+    fn detects_still_life_and_blinker_periods() {
+        let block = Grid::from_ascii("......\n.##...\n.##...\n......\n", '#').unwrap();
+        assert_eq!(detect_period(&block, 10), Some(1));
+
+        let blinker = Grid::from_ascii(".....\n.....\n.###.\n.....\n.....\n", '#').unwrap();
+        assert_eq!(detect_period(&blinker, 10), Some(2));
+        assert_eq!(detect_period(&blinker, 1), None);
+    }
+
+    #[test]
+    fn zero_steps_is_the_seed_and_one_step_is_one_tick() {
+        let seed = generate_grid_from_seed("alice", "secret", 20, 12);
+        let unchanged = run_simulation(seed.clone(), 0);
+        assert_eq!(unchanged, seed);
+        assert_eq!(hash_grid(&unchanged), hash_grid(&seed));
+        assert_eq!(run_simulation(seed.clone(), 1), tick(&seed));
+        assert_eq!(run_simulation(seed.clone(), 2), tick(&tick(&seed)));
+    }
+
+    #[test]
+    fn neighbor_counts_match_explicit_wrapping() {
+        for grid in test_grids() {
+            for y in 0..grid.height() as isize {
+                for x in 0..grid.width() as isize {
+                    assert_eq!(count_neighbors(&grid, x, y), naive_neighbors(&grid, x, y), "({}, {})", x, y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interior_fast_path_matches_at_the_center_and_every_edge() {
+        let mut grid: Grid<u8> = Grid::new(12, 9);
+        for (x, y) in [(5, 4), (6, 4), (7, 4), (0, 0), (11, 0), (0, 8), (11, 8), (4, 0), (5, 0), (0, 3), (0, 4)] {
+            grid.set(x, y, 1);
+        }
+        for (x, y) in [(11, 5), (11, 6), (7, 8), (8, 8), (9, 8)] {
+            grid.set(x, y, 1);
+        }
+
+        let mut expected = grid.clone();
+        for _ in 0..4 {
+            expected = naive_tick(&expected);
+            grid = tick(&grid);
+            assert_eq!(grid, expected);
+        }
+    }
+
+    #[test]
+    fn timed_run_records_every_tick() {
+        let seed = generate_grid_from_seed("alice", "secret", 64, 64);
+        let (grid, durations) = run_simulation_timed(seed.clone(), 12);
+        assert_eq!(durations.len(), 12);
+        assert!(durations.iter().all(|duration| !duration.is_zero()));
+        assert_eq!(grid, run_simulation(seed, 12));
+    }
+
+    #[test]
+    fn streaming_hashes_match_the_one_shot_hash() {
+        for grid in test_grids() {
+            let expected = hash_grid(&grid);
+            for chunk in [1, 7, grid.width(), usize::MAX] {
+                assert_eq!(hash_grid_streaming(&grid, chunk), expected);
+            }
+            for steps in [0, 1, 5] {
+                let expected = hash_grid(&run_simulation(grid.clone(), steps));
+                assert_eq!(simulate_and_hash_streaming(grid.clone(), steps), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn rng_streams_are_reproducible() {
+        let digest = Sha256::digest(b"inkverify");
+        let first: Vec<u32> = {
+            let mut rng = DetRng::from_bytes(&digest);
+            (0..64).map(|_| rng.next_u32()).collect()
+        };
+
+        let mut rng = DetRng::from_bytes(&digest);
+        assert_eq!((0..64).map(|_| rng.next_u32()).collect::<Vec<_>>(), first);
+        // Reseeding mid-stream from the same bytes restarts the same stream.
+        rng = DetRng::from_bytes(&digest);
+        assert_eq!(rng.next_u32(), first[0]);
+        assert_eq!(rng.next_u64(), (u64::from(first[1]) << 32) | u64::from(first[2]));
+        let mut replay = DetRng::from_bytes(&digest);
+        for _ in 0..3 {
+            replay.next_u32();
+        }
+        assert_eq!(rng, replay);
+
+        assert_ne!(DetRng::from_bytes(&Sha256::digest(b"other")).next_u32(), first[0]);
+        assert_eq!(DetRng::from_bytes(&[0; 32]), DetRng::new(0));
+        assert_ne!(DetRng::from_bytes(&[0, 0, 0, 0, 1]), DetRng::new(0));
+    }
+
+    #[test]
+    fn repeated_steps_match_run_simulation() {
+        for grid in test_grids() {
+            let mut stepped = grid.clone();
+            for _ in 0..9 {
+                stepped = step(&stepped, &Rule::INK);
+            }
+            assert_eq!(stepped, run_simulation(grid, 9));
+        }
+    }
+
+    #[test]
+    fn extinction_is_reported_when_it_happens() {
+        assert_eq!(run_simulation_extinction(Grid::new(8, 8), 10), (Grid::new(8, 8), Some(0)));
+
+        let mut lonely = Grid::new(8, 8);
+        lonely.set(3, 3, 1);
+        assert_eq!(run_simulation_extinction(lonely.clone(), 10), (Grid::new(8, 8), Some(1)));
+        // A run that ends before the die-out reports nothing.
+        assert_eq!(run_simulation_extinction(lonely.clone(), 0), (lonely, None));
+
+        let block = Grid::from_ascii("....\n.##.\n.##.\n....\n", '#').unwrap();
+        assert_eq!(run_simulation_extinction(block.clone(), 10), (block, None));
+    }
+
+    #[test]
+    fn droplets_reproduce_and_cluster() {
+        let droplets = generate_grid_droplets("alice", "secret", 64, 48, 12);
+        assert_eq!(droplets, generate_grid_droplets("alice", "secret", 64, 48, 12));
+        assert_ne!(droplets, generate_grid_droplets("bob", "secret", 64, 48, 12));
+        assert_eq!(generate_grid_droplets("alice", "secret", 64, 48, 0), Grid::new(64, 48));
+
+        // Live cells in discs mostly touch other live cells; coin-flip seeding at
+        // the same density gives them `8 * density` live neighbors on average.
+        let alive: Vec<(isize, isize)> = (0..48)
+            .flat_map(|y| (0..64).map(move |x| (x, y)))
+            .filter(|&(x, y)| droplets.get(x, y) == 1)
+            .collect();
+        let density = alive.len() as f64 / (64.0 * 48.0);
+        let neighbors: usize = alive.iter().map(|&(x, y)| usize::from(naive_neighbors(&droplets, x, y))).sum();
+        let mean_neighbors = neighbors as f64 / alive.len() as f64;
+        assert!(!alive.is_empty());
+        assert!(mean_neighbors > 8.0 * density + 2.0, "{} neighbors at density {}", mean_neighbors, density);
+    }
+
+    #[test]
+    fn initial_hash_is_stable_and_differs_from_the_final_hash() {
+        let seed = generate_grid_from_seed("alice", "secret", 32, 32);
+        let initial = hash_grid(&seed);
+        assert_eq!(initial, hash_grid(&generate_grid_from_seed("alice", "secret", 32, 32)));
+        assert_eq!(initial, hex::encode(Sha256::digest(seed.as_raw())));
+        assert_eq!(initial, HashAlgo::Sha256.hash_grid(&seed, &[]));
+        assert_ne!(initial, hash_grid(&run_simulation(seed, 10)));
+    }
+
+    #[test]
+    fn neighbor_count_buffer_matches_count_neighbors() {
+        let mut counts = Vec::new();
+        for grid in test_grids() {
+            grid.neighbor_counts_into(&mut counts, Neighborhood::Moore);
+            let (width, height) = (grid.width() as isize, grid.height() as isize);
+            let expected: Vec<u8> = (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| count_neighbors(&grid, x, y))
+                .collect();
+            assert_eq!(counts, expected);
+
+            let ptr = counts.as_ptr();
+            grid.clone().into_layout(Layout::ColMajor).neighbor_counts_into(&mut counts, Neighborhood::Moore);
+            assert_eq!(counts, expected);
+            assert_eq!(counts.as_ptr(), ptr);
+        }
+    }
+
+    #[test]
+    fn difficulty_levels_strictly_increase() {
+        assert_eq!(params_for_difficulty(0), (64, 64, 100));
+        assert_eq!(params_for_difficulty(3), (192, 192, 400));
+        for level in 1..=MAX_DIFFICULTY_LEVEL {
+            let (width, height, steps) = params_for_difficulty(level);
+            let (prev_width, prev_height, prev_steps) = params_for_difficulty(level - 1);
+            assert_eq!(width, height);
+            assert!(width * height > prev_width * prev_height && steps > prev_steps, "level {}", level);
+            assert_eq!(params_for_difficulty(level), (width, height, steps));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Difficulty level must be at most")]
+    fn difficulty_past_the_maximum_panics() {
+        params_for_difficulty(MAX_DIFFICULTY_LEVEL + 1);
+    }
+
+    #[test]
+    fn contexts_bind_the_hash() {
+        let grid = run_simulation(generate_grid_from_seed("alice", "secret", 32, 32), 10);
+        let plain = hash_grid(&grid);
+        assert_eq!(hash_grid_with_context(&grid, &[]), plain);
+
+        let session_a = hash_grid_with_context(&grid, b"session-a");
+        let session_b = hash_grid_with_context(&grid, b"session-b");
+        assert_eq!(session_a, hash_grid_with_context(&grid, b"session-a"));
+        assert_ne!(session_a, session_b);
+        assert_ne!(session_a, plain);
+
+        let mut with_context = Sha256::new();
+        with_context.update(grid.as_raw());
+        with_context.update(b"session-a");
+        assert_eq!(session_a, hex::encode(with_context.finalize()));
+    }
+
+    #[test]
+    fn neighbor_histogram_counts_a_handcrafted_grid() {
+        let mut single = Grid::new(5, 5);
+        single.set(2, 2, 1);
+        assert_eq!(single.neighbor_histogram(Neighborhood::Moore), [17, 8, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(single.neighbor_histogram(Neighborhood::VonNeumann), [21, 4, 0, 0, 0, 0, 0, 0, 0]);
+
+        // A domino on a 3x3 torus: every other cell sees both live cells.
+        let domino = Grid::from_ascii("##.\n...\n...\n", '#').unwrap();
+        assert_eq!(domino.neighbor_histogram(Neighborhood::Moore), [0, 2, 7, 0, 0, 0, 0, 0, 0]);
+
+        let seeded = generate_grid_from_seed("alice", "secret", 30, 20);
+        assert_eq!(seeded.neighbor_histogram(Neighborhood::Moore).iter().sum::<usize>(), 600);
+    }
+
+    #[test]
+    fn generations_start_one_tick_in() {
+        for grid in test_grids() {
+            for n in [0, 1, 6] {
+                assert_eq!(generations(grid.clone(), Rule::INK).nth(n), Some(run_simulation(grid.clone(), n + 1)));
+            }
+            let column_major = grid.clone().into_layout(Layout::ColMajor);
+            let third = generations(column_major, Rule::INK).nth(2).unwrap();
+            assert_eq!(third.layout(), Layout::ColMajor);
+            assert_eq!(third, run_simulation(grid, 3));
+        }
+    }
+
+    #[test]
+    fn seeding_fill_matches_a_serial_reference() {
+        // With `parallel` the fill is split into chunks that each jump the PRNG
+        // ahead; one draw per cell in order is the reference either way.
+        let digest = derive_seed("alice", "secret", 1);
+        let mut rng = DetRng::from_bytes(&digest);
+        let serial: Vec<u8> = (0..1000 * 1000).map(|_| rng.next_bool(DEFAULT_DENSITY)).collect();
+        assert_eq!(generate_grid_from_seed("alice", "secret", 1000, 1000).into_raw(), serial);
+
+        let mut jumped = DetRng::from_bytes(&digest);
+        jumped.jump(1000 * 1000);
+        assert_eq!(jumped, rng);
+    }
+
+    #[test]
+    fn every_hash_algorithm_is_stable_at_its_length() {
+        let grid = run_simulation(generate_grid_from_seed("alice", "secret", 24, 24), 5);
+        let sha256 = HashAlgo::Sha256.digest(&grid, &[]);
+        for algo in [HashAlgo::Sha256, HashAlgo::Sha512, HashAlgo::Sha256Trunc(8), HashAlgo::Sha256Trunc(32)] {
+            let digest = algo.digest(&grid, &[]);
+            assert_eq!(digest.len(), algo.output_len());
+            assert_eq!(digest, algo.digest(&grid.clone(), &[]));
+            assert_eq!(algo.hash_grid(&grid, &[]), hex::encode(&digest));
+            assert_eq!(algo.to_string().parse(), Ok(algo));
+            if let HashAlgo::Sha256Trunc(len) = algo {
+                assert_eq!(digest, sha256[..len]);
+            }
+        }
+        assert_eq!(HashAlgo::Sha512.digest(&grid, &[]), Sha512::digest(grid.as_raw()).to_vec());
+
+        assert!("sha256t7".parse::<HashAlgo>().is_err());
+        assert!("sha256t33".parse::<HashAlgo>().is_err());
+        assert!("md5".parse::<HashAlgo>().is_err());
+    }
+
+    #[test]
+    fn components_are_counted_across_the_seam() {
+        let count = |text: &str, neighborhood| {
+            Grid::from_ascii(text, '#').unwrap().connected_components(neighborhood).1
+        };
+        assert_eq!(count("....\n....\n....\n", Neighborhood::Moore), 0);
+        assert_eq!(count("....\n.##.\n..#.\n", Neighborhood::VonNeumann), 1);
+        assert_eq!(count("#.....\n......\n...##.\n", Neighborhood::Moore), 2);
+        // Opposite edges touch on the torus.
+        assert_eq!(count("......\n#....#\n......\n", Neighborhood::VonNeumann), 1);
+        // Diagonal corners: one blob for Moore, two for von Neumann.
+        assert_eq!(count("#.....\n......\n.....#\n", Neighborhood::Moore), 1);
+        assert_eq!(count("#.....\n......\n.....#\n", Neighborhood::VonNeumann), 2);
+
+        let grid = Grid::from_ascii("..#\n#..\n...\n..#\n", '#').unwrap();
+        let (labels, components) = grid.connected_components(Neighborhood::VonNeumann);
+        assert_eq!(components, 2);
+        assert_eq!(labels.as_raw(), &[0, 0, 1, 2, 0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn digests_with_a_zero_prefix_still_seed_distinct_grids() {
+        let mut first = [0u8; 32];
+        let mut second = [0u8; 32];
+        first[4..8].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        second[31] = 0x9a;
+
+        let grids = [first, second, [0; 32]].map(|digest| grid_from_digest(&digest, 32, 32, DEFAULT_DENSITY));
+        assert_ne!(grids[0], grids[1]);
+        assert_ne!(grids[0], grids[2]);
+        assert_ne!(grids[1], grids[2]);
+        // Only an all-zero fold falls back to the fixed seed.
+        assert_eq!(DetRng::from_bytes(&[0; 32]), DetRng::new(0xDEADBEEF));
+        assert_eq!(DetRng::from_bytes(&first), DetRng::new(0x12345678));
+    }
+
+    #[test]
+    fn rule_sequences_differ_from_either_rule_alone() {
+        let seed = generate_grid_from_seed("alice", "secret", 48, 48);
+        let (life, high_life) = (Rule::INK, Rule::new(&[3, 6], &[2, 3]));
+        let step_with = |rule: &Rule| (0..40).fold(seed.clone(), |grid, _| step(&grid, rule));
+
+        let mixed = run_simulation_rules(seed.clone(), 40, &[life, high_life]);
+        assert_ne!(mixed, step_with(&life));
+        assert_ne!(mixed, step_with(&high_life));
+        assert_eq!(run_simulation_rules(seed.clone(), 40, &[life]), run_simulation(seed.clone(), 40));
+
+        let mut manual = seed.clone();
+        for i in 0..5 {
+            manual = step(&manual, if i % 2 == 0 { &life } else { &high_life });
+        }
+        assert_eq!(run_simulation_rules(seed.clone(), 5, &[life, high_life]), manual);
+        assert_eq!(run_simulation_rules(seed.clone(), 0, &[]), seed);
+    }
+
+    #[test]
+    fn metered_runs_count_every_cell_update() {
+        let seed = generate_grid_from_seed("alice", "secret", 30, 20);
+        let (grid, metrics) = run_simulation_metered(seed.clone(), 25);
+        assert_eq!(grid, run_simulation(seed, 25));
+        assert_eq!(metrics.cells_updated, 25 * 30 * 20);
+        assert!(metrics.cells_per_sec >= 0.0);
+
+        let exact = SimMetrics::new(1000, 500, Duration::from_millis(250));
+        assert_eq!(exact.cells_updated, 500_000);
+        assert_eq!(exact.cells_per_sec, 2_000_000.0);
+        assert_eq!(SimMetrics::new(1000, 500, Duration::ZERO).cells_per_sec, 0.0);
+    }
+
+    #[test]
+    fn stop_flag_ends_the_run_between_ticks() {
+        let seed = generate_grid_from_seed("alice", "secret", 32, 32);
+        let stop = AtomicBool::new(true);
+        assert_eq!(run_simulation_until(seed.clone(), 50, &stop), (seed.clone(), RunOutcome::Interrupted(0)));
+        assert_eq!(run_simulation_until(seed.clone(), 0, &stop), (seed.clone(), RunOutcome::Completed));
+        assert_eq!(run_simulation_until(Grid::new(4, 4), 50, &stop), (Grid::new(4, 4), RunOutcome::Extinct(0)));
+
+        let go = AtomicBool::new(false);
+        let completed = (run_simulation(seed.clone(), 50), RunOutcome::Completed);
+        assert_eq!(run_simulation_until(seed.clone(), 50, &go), completed);
+
+        // Under `Every(16)` the flag is only read every 16 ticks, so a stop set
+        // from another thread lands on a multiple of 16.
+        let stop = AtomicBool::new(false);
+        let (grid, outcome) = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(20));
+                stop.store(true, Ordering::Relaxed);
+            });
+            run_simulation_until_checked(seed.clone(), usize::MAX, &stop, CheckInterval::Every(16))
+        });
+        let RunOutcome::Interrupted(done) = outcome else { panic!("{:?}", outcome) };
+        assert_eq!(done % 16, 0);
+        assert_eq!(grid, run_simulation(seed, done));
+    }
+
+    #[test]
+    fn vdf_chains_every_tick_on_the_previous_hash() {
+        let vdf = run_vdf("alice", "secret", 24, 24, 6);
+        assert_eq!(vdf, run_vdf("alice", "secret", 24, 24, 6));
+        assert_ne!(vdf, hash_grid(&run_simulation(generate_grid_from_seed("alice", "secret", 24, 24), 6)));
+        assert_eq!(
+            run_vdf("alice", "secret", 24, 24, 0),
+            hash_grid(&generate_grid_from_seed("alice", "secret", 24, 24))
+        );
+
+        // The same chain, spelled out: flip the cells the current hash picks, then tick.
+        let mut grid = generate_grid_from_seed("alice", "secret", 24, 24);
+        for _ in 0..6 {
+            let mut rng = DetRng::from_bytes(&Sha256::digest(grid.as_raw()));
+            for _ in 0..VDF_PERTURBED_CELLS {
+                let index = (rng.next_u64() % (24 * 24)) as usize;
+                grid.as_raw_mut()[index] ^= 1;
+            }
+            grid = tick(&grid);
+        }
+        assert_eq!(vdf, hash_grid(&grid));
+    }
+
+    #[test]
+    fn stretched_seeds_depend_on_the_iteration_count() {
+        let plain = generate_grid_from_seed("alice", "secret", 32, 32);
+        assert_eq!(generate_grid_stretched("alice", "secret", 32, 32, 1), plain);
+
+        let stretched = generate_grid_stretched("alice", "secret", 32, 32, 1000);
+        assert_ne!(stretched, plain);
+        assert_ne!(stretched, generate_grid_stretched("alice", "secret", 32, 32, 999));
+        assert_eq!(stretched, generate_grid_stretched("alice", "secret", 32, 32, 1000));
+
+        let once = derive_seed("alice", "secret", 1);
+        assert_eq!(derive_seed("alice", "secret", 3), <[u8; 32]>::from(Sha256::digest(Sha256::digest(once))));
+    }
+
+    #[test]
+    fn weighted_counts_generalize_the_plain_count() {
+        let uniform = WeightedNeighborhood::uniform(Neighborhood::Moore);
+        for grid in test_grids() {
+            assert_eq!(tick_weighted(&grid, &Rule::INK, &uniform), step(&grid, &Rule::INK));
+        }
+
+        // One live cell weighing 3 is enough for a birth to its lower right.
+        let weight = |offset| if offset == (-1, -1) { 3 } else { 1 };
+        let heavy_top_left = WeightedNeighborhood {
+            offsets: uniform.offsets.iter().map(|&(offset, _)| (offset, weight(offset))).collect(),
+        };
+        let mut lonely = Grid::new(5, 5);
+        lonely.set(1, 1, 1);
+        let mut expected = Grid::new(5, 5);
+        expected.set(2, 2, 1);
+        assert_eq!(tick_weighted(&lonely, &Rule::INK, &heavy_top_left), expected);
+        assert_eq!(tick_weighted(&lonely, &Rule::INK, &uniform), Grid::new(5, 5));
+    }
+
+    #[test]
+    fn timeouts_stop_on_an_exact_tick() {
+        let seed = generate_grid_from_seed("alice", "secret", 128, 128);
+        let (grid, completed, timed_out) = run_simulation_timeout(seed.clone(), 1_000_000, Duration::from_millis(5));
+        assert!(timed_out);
+        assert!(completed < 1_000_000);
+        assert_eq!(grid, run_simulation(seed.clone(), completed));
+
+        assert_eq!(run_simulation_timeout(seed.clone(), 10, Duration::ZERO).1, 0);
+        let (grid, completed, timed_out) = run_simulation_timeout(seed.clone(), 10, Duration::from_secs(60));
+        assert_eq!((completed, timed_out), (10, false));
+        assert_eq!(grid, run_simulation(seed, 10));
+    }
+
+    #[test]
+    fn one_call_hash_matches_the_two_step_path() {
+        for grid in test_grids() {
+            for steps in [0, 1, 37] {
+                assert_eq!(run_simulation_hash(grid.clone(), steps), hash_grid(&run_simulation(grid.clone(), steps)));
+            }
+        }
+    }
+
+    #[test]
+    fn permutations_visit_every_index_once() {
+        for n in [0, 1, 2, 3, 5, 16, 17, 1000, 4099] {
+            for seed in [0, 1, 0xDEAD_BEEF] {
+                let order: Vec<usize> = permutation(seed, n).collect();
+                assert_eq!(order.len(), n);
+                let mut sorted = order.clone();
+                sorted.sort_unstable();
+                assert!(sorted.iter().copied().eq(0..n), "n = {}, seed = {}", n, seed);
+                assert_eq!(permutation(seed, n).collect::<Vec<_>>(), order);
+            }
+        }
+        assert_ne!(permutation(1, 1000).collect::<Vec<_>>(), permutation(2, 1000).collect::<Vec<_>>());
+        assert!(!permutation(1, 1000).eq(0..1000));
+    }
+
+    #[test]
+    fn adaptive_checks_are_sparser_on_cheaper_grids() {
+        let policy = CheckInterval::Adaptive { target: Duration::from_millis(50) };
+        assert_eq!(policy.ticks_between(Duration::from_micros(10)), 5000);
+        assert_eq!(policy.ticks_between(Duration::from_millis(10)), 5);
+        assert_eq!(policy.ticks_between(Duration::from_secs(1)), 1);
+        assert_eq!(policy.ticks_between(Duration::ZERO), 50_000_000);
+        assert_eq!(CheckInterval::Every(0).ticks_between(Duration::from_secs(1)), 1);
+        assert_eq!(CheckInterval::Every(8).ticks_between(Duration::from_micros(1)), 8);
+
+        // Measured: a 16x16 tick is ~1000x cheaper than a 512x512 one.
+        let tick_time = |side| {
+            let grid = generate_grid_from_seed("alice", "secret", side, side);
+            let start = Instant::now();
+            std::hint::black_box(tick(&grid));
+            start.elapsed()
+        };
+        assert!(policy.ticks_between(tick_time(16)) > policy.ticks_between(tick_time(512)));
+    }
+
+    #[test]
+    fn faster_probes_get_more_work() {
+        let work = |ms| {
+            let (width, height, steps) = recommended_params(ms);
+            width * height * steps
+        };
+        let mut probes: Vec<f64> = (0..200).map(|i| 0.01 * 1.1f64.powi(i)).collect();
+        probes.insert(0, 0.0);
+        for pair in probes.windows(2) {
+            assert!(work(pair[0]) >= work(pair[1]), "{} ms vs {} ms", pair[0], pair[1]);
+        }
+        assert!(work(1.0) > work(1000.0));
+        assert_eq!(recommended_params(0.0), params_for_difficulty(MAX_RECOMMENDED_LEVEL));
+        assert_eq!(recommended_params(-1.0), params_for_difficulty(0));
+        assert_eq!(recommended_params(f64::NAN), params_for_difficulty(0));
+        assert_eq!(recommended_params(f64::INFINITY), params_for_difficulty(0));
+        assert_eq!(recommended_params(12.5), recommended_params(12.5));
+    }
+
+    #[test]
+    fn center_heavy_gradients_are_denser_in_the_middle() {
+        let uniform = generate_grid_gradient("alice", "secret", 40, 30, DEFAULT_DENSITY, DEFAULT_DENSITY);
+        assert_eq!(uniform, generate_grid_from_seed("alice", "secret", 40, 30));
+
+        let grid = generate_grid_gradient("alice", "secret", 200, 200, 240, 16);
+        let density = |cells: &[(usize, usize)]| {
+            cells.iter().filter(|&&(x, y)| grid.get(x as isize, y as isize) != 0).count() as f64 / cells.len() as f64
+        };
+        let center: Vec<_> = (80..120).flat_map(|y| (80..120).map(move |x| (x, y))).collect();
+        let edge: Vec<_> = (0..200)
+            .flat_map(|y| (0..200).map(move |x| (x, y)))
+            .filter(|&(x, y)| x.min(199 - x).min(y).min(199 - y) < 10)
+            .collect();
+        let (center, edge) = (density(&center), density(&edge));
+        assert!(center > 0.75 && edge < 0.25, "center {:.2}, edge {:.2}", center, edge);
+    }
+
+    #[test]
+    fn replay_halves_compose_into_the_whole_range() {
+        let seed = generate_grid_from_seed("alice", "secret", 24, 24);
+        let (whole, whole_hash) = replay_range(seed.clone(), 0, 100);
+        let (mid, _) = replay_range(seed.clone(), 0, 50);
+        let (second, second_hash) = replay_range(mid, 50, 100);
+        assert_eq!(second, whole);
+        assert_eq!(second_hash, whole_hash);
+        assert_eq!(hex::encode(whole_hash), hash_grid(&run_simulation(seed.clone(), 100)));
+
+        let (empty, empty_hash) = replay_range(seed.clone(), 7, 7);
+        assert_eq!((empty, empty_hash), (seed.clone(), seed.logical_hash()));
+    }
+
+    #[test]
+    #[should_panic(expected = "runs backwards")]
+    fn backwards_replays_panic() {
+        replay_range(Grid::new(4, 4), 5, 4);
+    }
+
+    #[test]
+    fn path_bound_batches_bind_the_trajectory() {
+        let seed = generate_grid_from_seed("alice", "secret", 20, 14);
+        // Per-tick reference: step index and grid after every tick.
+        let mut hasher = Sha256::new();
+        let mut grid = seed.clone();
+        for step in 0..=10u64 {
+            if step > 0 {
+                grid = tick(&grid);
+            }
+            hasher.update(step.to_le_bytes());
+            hasher.update(grid.as_raw());
+        }
+        assert_eq!(run_path_bound(seed.clone(), 10, 1), hex::encode(hasher.finalize()));
+
+        let hashes: std::collections::HashSet<String> =
+            [1, 2, 3, 10].iter().map(|&batch| run_path_bound(seed.clone(), 10, batch)).collect();
+        assert_eq!(hashes.len(), 4);
+        assert_eq!(run_path_bound(seed.clone(), 10, 10), run_path_bound(seed.clone(), 10, 1000));
+        assert_ne!(run_path_bound(seed.clone(), 10, 10), hash_grid(&run_simulation(seed, 10)));
+    }
+
+    #[test]
+    fn identity_and_box_stencils_match_the_grid_and_its_counts() {
+        for grid in test_grids() {
+            let (w, h) = (grid.width() as isize, grid.height() as isize);
+            let identity = grid.convolve(&[(0, 0, 1)]);
+            let shifted = grid.convolve(&[(1, 0, 1)]);
+            let mut stencil: Vec<_> = MOORE_OFFSETS.iter().map(|&(dx, dy)| (dx, dy, 2)).collect();
+            stencil.push((0, 0, 1));
+            let weighted_box = grid.convolve(&stencil);
+            for y in 0..h {
+                for x in 0..w {
+                    let cell = i32::from(grid.get(x, y));
+                    assert_eq!(identity.get(x, y), cell);
+                    assert_eq!(shifted.get(x, y), i32::from(grid.get(x + 1, y)));
+                    let sum = 2 * i32::from(naive_neighbors(&grid, x, y)) + cell;
+                    assert_eq!(weighted_box.get(x, y), sum, "({}, {})", x, y);
+                }
+            }
+        }
+        assert_eq!(Grid::new(3, 3).convolve(&[]), Grid::new(3, 3));
+    }
+
+    #[test]
+    fn warmups_skip_into_the_stream_and_reproduce() {
+        let warmed = |width, height, warmup| generate_grid_warmed("alice", "secret", width, height, 1, warmup);
+        assert_eq!(warmed(32, 32, 0), generate_grid_from_seed("alice", "secret", 32, 32));
+        assert_ne!(warmed(32, 32, 16), warmed(32, 32, 0));
+        assert_eq!(warmed(32, 32, 16), warmed(32, 32, 16));
+        assert_ne!(warmed(32, 32, 16), warmed(32, 32, 17));
+
+        // One draw per cell: the warm-up drops exactly the first 16 cells of the fill.
+        assert_eq!(warmed(80, 1, 16).as_raw(), &warmed(96, 1, 0).as_raw()[16..]);
+    }
+
+    #[test]
+    fn credential_boundaries_are_part_of_the_seed() {
+        let ambiguous = [("ab", "c"), ("a", "bc"), ("abc", ""), ("", "abc")];
+        let grids: Vec<_> = ambiguous.iter().map(|&(u, p)| generate_grid_from_seed(u, p, 32, 32)).collect();
+        let seeds: Vec<_> = ambiguous.iter().map(|&(u, p)| derive_seed(u, p, 1)).collect();
+        for i in 0..grids.len() {
+            for j in i + 1..grids.len() {
+                assert_ne!(grids[i], grids[j], "{:?} vs {:?}", ambiguous[i], ambiguous[j]);
+                assert_ne!(seeds[i], seeds[j]);
+            }
+        }
+        assert_ne!(generate_grid_with_nonce("ab", "c", 3, 16, 16), generate_grid_with_nonce("a", "bc", 3, 16, 16));
+    }
+
+    #[test]
+    fn bounded_generations_stop_after_max_items() {
+        let seed = generate_grid_from_seed("alice", "secret", 20, 20);
+        let frames: Vec<_> = bounded_generations(seed.clone(), Rule::INK, 5).collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames.last(), Some(&run_simulation(seed.clone(), 5)));
+        assert_eq!(frames[0], tick(&seed));
+        assert_eq!(bounded_generations(seed, Rule::INK, 0).count(), 0);
+    }
+}
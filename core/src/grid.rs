@@ -1,4 +1,84 @@
+use crate::rules::MOORE_OFFSETS;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Largest number of cells a `Grid` may hold.
+///
+/// Coordinates are resolved with `isize` math (to allow negative, wrapping reads),
+/// so every dimension and index must fit in `isize`. `width * height` is checked
+/// against this with `checked_mul` rather than silently overflowing.
+pub const MAX_CELLS: usize = isize::MAX as usize;
+
+/// Errors from constructing or combining grids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridError {
+    /// `width * height` overflows or exceeds the documented maximum.
+    TooLarge { width: usize, height: usize },
+    /// The supplied data does not contain `width * height` cells.
+    DimensionMismatch { expected: usize, actual: usize },
+    /// Two grids that must be the same size are not.
+    SizeMismatch { expected: (usize, usize), actual: (usize, usize) },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::TooLarge { width, height } => {
+                write!(f, "Grid {}x{} exceeds the maximum cell count", width, height)
+            }
+            GridError::DimensionMismatch { expected, actual } => {
+                write!(f, "Expected {} cells but got {}", expected, actual)
+            }
+            GridError::SizeMismatch { expected, actual } => {
+                write!(f, "Expected a {}x{} grid but got {}x{}", expected.0, expected.1, actual.0, actual.1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// Returns `width * height`, or `GridError::TooLarge` if it overflows `MAX_CELLS`.
+pub fn cell_count(width: usize, height: usize) -> Result<usize, GridError> {
+    width
+        .checked_mul(height)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .ok_or(GridError::TooLarge { width, height })
+}
+
+/// What a read past the edge of the grid sees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Boundary {
+    /// Edges wrap around (a torus), so there are no walls. The InkVerify default.
+    #[default]
+    Torus,
+    /// Everything outside the grid reads as empty (`T::default()`).
+    Dead,
+    /// Edges act as mirrors: a read past an edge returns the in-grid cell at
+    /// the same distance from it, edge cell included (`x = width` reads
+    /// `width - 1`, `x = -1` reads `0`). Nothing leaks across opposite edges.
+    Reflective,
+}
+
+/// How a grid's cells are ordered in memory, chosen when the grid is built
+/// (`Grid::with_layout`).
+///
+/// Only the mapping from (x, y) to a slot changes: `get`, `set` and every tick
+/// see the same logical grid either way. The raw buffer (`as_raw`, and so
+/// `hash_grid`) is in storage order, so proofs, hashes and file formats are
+/// defined over `RowMajor`, the layout every generator produces. The
+/// row-sliced fast paths (the row tick, streaming hashes, image and file
+/// encoders) handle `ColMajor` through a per-cell fallback or a row-major copy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Row after row: (x, y) is slot `y * width + x`. The InkVerify default.
+    #[default]
+    RowMajor,
+    /// Column after column: (x, y) is slot `x * height + y`.
+    ColMajor,
+}
 
 /// The core Memory-Hard container.
 ///
@@ -8,42 +88,86 @@ use std::fmt;
 /// Performance Note:
 /// Using a flat vector `Vec<T>` instead of `Vec<Vec<T>>` improves CPU cache locality
 /// and prevents memory fragmentation. It allows the CPU to pre-fetch data efficiently.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Equality and hashing cover the dimensions and every cell in logical
+/// (row-major) order, not the layout: a column-major copy equals the original
+/// and hashes the same, so grids in either layout can key a `HashSet` for
+/// state-repeat detection.
+#[derive(Clone, Debug)]
 pub struct Grid<T> {
     width: usize,
     height: usize,
+    layout: Layout,
     cells: Vec<T>,
 }
 
 impl<T> Grid<T>
 where
-    T: Clone + Copy + Default,
+    T: Clone + Default,
 {
     /// Creates a new grid of the specified size, initialized with default values (0).
+    /// Panics if `width * height` exceeds `MAX_CELLS` (see `try_new`).
     pub fn new(width: usize, height: usize) -> Self {
-        Grid {
+        Self::try_new(width, height).expect("Grid dimensions too large")
+    }
+
+    /// Fallible version of `new`: errors instead of overflowing on huge dimensions.
+    pub fn try_new(width: usize, height: usize) -> Result<Self, GridError> {
+        let cells = cell_count(width, height)?;
+        Ok(Grid {
             width,
             height,
-            cells: vec![T::default(); width * height],
-        }
+            layout: Layout::RowMajor,
+            cells: vec![T::default(); cells],
+        })
     }
 
-    /// Creates a new grid from a raw vector of data.
+    /// Same as `new`, with the cells stored in `layout` order.
+    pub fn with_layout(width: usize, height: usize, layout: Layout) -> Self {
+        Grid { layout, ..Self::new(width, height) }
+    }
+
+    /// Creates a new (row-major) grid from a raw vector of data.
     /// Panics if the vector size does not match width * height.
     /// Useful when initializing the grid from a hash seed.
     pub fn from_raw(width: usize, height: usize, cells: Vec<T>) -> Self {
         assert_eq!(
-            cells.len(),
-            width * height,
+            Ok(cells.len()),
+            cell_count(width, height),
             "Cell count does not match grid dimensions"
         );
         Grid {
             width,
             height,
+            layout: Layout::RowMajor,
             cells,
         }
     }
 
+    /// Creates a new (row-major) grid from exactly `width * height` items,
+    /// filling the grid's own buffer directly: no intermediate `Vec` for a
+    /// `map`/`zip` pipeline.
+    ///
+    /// Errors with `GridError::DimensionMismatch` if the iterator runs out early
+    /// (`actual` = items it yielded) or has items left over (`actual` =
+    /// `expected + 1`: the rest is not drained, so endless iterators are fine).
+    pub fn from_iter_sized<I: IntoIterator<Item = T>>(width: usize, height: usize, iter: I) -> Result<Self, GridError> {
+        let expected = cell_count(width, height)?;
+        let mut iter = iter.into_iter();
+
+        // Trust the size hint only up to the grid size, so a bad hint cannot
+        // cause a huge allocation.
+        let mut cells = Vec::with_capacity(iter.size_hint().0.min(expected));
+        cells.extend(iter.by_ref().take(expected));
+        if cells.len() != expected {
+            return Err(GridError::DimensionMismatch { expected, actual: cells.len() });
+        }
+        if iter.next().is_some() {
+            return Err(GridError::DimensionMismatch { expected, actual: expected + 1 });
+        }
+        Ok(Grid { width, height, layout: Layout::RowMajor, cells })
+    }
+
     /// Returns the width of the grid.
     pub fn width(&self) -> usize {
         self.width
@@ -54,12 +178,53 @@ where
         self.height
     }
 
+    /// How the cells are ordered in memory (see `Layout`).
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The same logical grid stored in `layout` order (no copy if it already is).
+    pub fn into_layout(self, layout: Layout) -> Grid<T> {
+        if layout == self.layout {
+            return self;
+        }
+        let mut out = Grid::with_layout(self.width, self.height, layout);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let idx = out.index(x, y);
+                out.cells[idx] = self.cells[self.index(x, y)].clone();
+            }
+        }
+        out
+    }
+
+    /// `self` if it is row-major, otherwise a row-major copy, for code that
+    /// works on whole rows of the raw buffer.
+    pub(crate) fn row_major(&self) -> Cow<'_, Grid<T>> {
+        match self.layout {
+            Layout::RowMajor => Cow::Borrowed(self),
+            Layout::ColMajor => Cow::Owned(self.clone().into_layout(Layout::RowMajor)),
+        }
+    }
+
+    /// Slot of in-range (x, y) in the flat vector, per the layout.
+    #[inline]
+    fn index(&self, x: usize, y: usize) -> usize {
+        match self.layout {
+            Layout::RowMajor => y * self.width + x,
+            Layout::ColMajor => x * self.height + y,
+        }
+    }
+
     /// Converts 2D (x, y) coordinates into a 1D index for the flat vector.
     ///
     /// Implements Toroidal Topology (Wrap-around):
     /// - If x is -1 (left of edge), it wraps to width-1 (right edge).
     /// - If y is height (below bottom), it wraps to 0 (top).
+    ///
     /// This ensures there are no "walls" to stop the chaos expansion.
+    /// The `as isize` casts are lossless because `cell_count` caps both
+    /// dimensions at `MAX_CELLS` (= `isize::MAX`).
     #[inline]
     fn get_index(&self, x: isize, y: isize) -> usize {
         // rem_euclid calculates the true mathematical modulo, handling negative numbers correctly.
@@ -67,55 +232,1305 @@ where
         let y_wrapped = y.rem_euclid(self.height as isize) as usize;
         let x_wrapped = x.rem_euclid(self.width as isize) as usize;
 
-        y_wrapped * self.width + x_wrapped
+        self.index(x_wrapped, y_wrapped)
     }
 
-    /// READS a cell's value at (x, y).
-    /// Safe to call with negative coordinates due to wrapping.
-    pub fn get(&self, x: isize, y: isize) -> T {
-        let idx = self.get_index(x, y);
-        // We use unsafe for maximum speed in production, but safe indexing here for stability.
-        self.cells[idx] 
+    /// READS a reference to the cell at (x, y), wrapping like `get`.
+    /// Works for any element type, including non-`Copy` ones like `String`.
+    pub fn get_ref(&self, x: isize, y: isize) -> &T {
+        &self.cells[self.get_index(x, y)]
     }
 
     /// WRITES a value to a cell at (x, y).
     /// Note: inputs are `usize` because we only write to valid coordinates
     /// during the update loop.
     pub fn set(&mut self, x: usize, y: usize, value: T) {
-        let idx = y * self.width + x;
+        let idx = self.index(x, y);
         self.cells[idx] = value;
     }
 
-    /// Returns a reference to the underlying raw data.
+    /// Writes `value` to every cell of the `w x h` rectangle starting at (x, y).
+    ///
+    /// Coordinates wrap like `get`, so a region hanging off the right/bottom edge
+    /// continues on the left/top instead of panicking. Regions larger than the grid
+    /// are clamped to the grid size (every cell is written once).
+    pub fn fill_region(&mut self, x: isize, y: isize, w: usize, h: usize, value: T) {
+        for dy in 0..h.min(self.height) {
+            for dx in 0..w.min(self.width) {
+                let idx = self.get_index(x + dx as isize, y + dy as isize);
+                self.cells[idx] = value.clone();
+            }
+        }
+    }
+
+    /// Resets every cell back to `T::default()` (0 for `Grid<u8>`).
+    pub fn clear(&mut self) {
+        self.cells.fill(T::default());
+    }
+
+    /// Returns a reference to the underlying raw data, in storage order
+    /// (row-major unless built with `Layout::ColMajor`).
     /// Critical for the final step where we hash the entire grid state.
     pub fn as_raw(&self) -> &[T] {
         &self.cells
     }
+
+    /// Returns a mutable view of the underlying raw data (in storage order).
+    /// The length is fixed, so the grid's dimensions stay valid.
+    pub fn as_raw_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// Consumes the grid and hands back its cells (in storage order) without copying them.
+    pub fn into_raw(self) -> Vec<T> {
+        self.cells
+    }
+
+    /// Returns a new grid with width and height swapped, so that
+    /// `out.get(y, x) == self.get(x, y)`.
+    pub fn transpose(&self) -> Grid<T> {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for x in 0..self.width {
+            for y in 0..self.height {
+                cells.push(self.cells[self.index(x, y)].clone());
+            }
+        }
+        Grid::from_raw(self.height, self.width, cells).into_layout(self.layout)
+    }
+
+    /// Transposes the grid in place.
+    /// Square grids are transposed by swapping across the diagonal (no allocation;
+    /// slots (x, y) and (y, x) are mirror images in either layout);
+    /// non-square grids fall back to `transpose()` since the layout must change.
+    pub fn transpose_in_place(&mut self) {
+        if self.width != self.height {
+            *self = self.transpose();
+            return;
+        }
+
+        let n = self.width;
+        for y in 0..n {
+            for x in (y + 1)..n {
+                self.cells.swap(y * n + x, x * n + y);
+            }
+        }
+    }
+
+    /// Returns a new grid with every cell moved by (dx, dy) on the torus, so that
+    /// `out.get(x, y) == self.get(x - dx, y - dy)`. Negative and oversized shifts
+    /// wrap, e.g. `shift(width, 0)` is the identity.
+    pub fn shift(&self, dx: isize, dy: isize) -> Grid<T> {
+        if self.cells.is_empty() {
+            return self.clone();
+        }
+
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for y in 0..self.height as isize {
+            for x in 0..self.width as isize {
+                cells.push(self.cells[self.get_index(x - dx, y - dy)].clone());
+            }
+        }
+        Grid::from_raw(self.width, self.height, cells).into_layout(self.layout)
+    }
+
+    /// Returns a `new_width x new_height` copy, anchored at the top-left corner:
+    /// cell (x, y) keeps its value wherever it exists in both grids. Growing fills
+    /// the new area with `T::default()`; shrinking crops, discarding every cell at
+    /// `x >= new_width` or `y >= new_height`. `self` is left unchanged.
+    ///
+    /// Panics if `new_width * new_height` exceeds `MAX_CELLS` (like `new`).
+    pub fn resized(&self, new_width: usize, new_height: usize) -> Grid<T> {
+        let mut out = Grid::with_layout(new_width, new_height, self.layout);
+        // Copy whole rows (or columns): the kept part of each is contiguous.
+        let (lines, old_len, new_len) = match self.layout {
+            Layout::RowMajor => (self.height.min(new_height), self.width, new_width),
+            Layout::ColMajor => (self.width.min(new_width), self.height, new_height),
+        };
+        let keep = old_len.min(new_len);
+        for line in 0..lines {
+            out.cells[line * new_len..line * new_len + keep]
+                .clone_from_slice(&self.cells[line * old_len..line * old_len + keep]);
+        }
+        out
+    }
+}
+
+impl<T: Clone + Default + PartialEq> PartialEq for Grid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if (self.width, self.height) != (other.width, other.height) {
+            return false;
+        }
+        if self.layout == other.layout {
+            return self.cells == other.cells;
+        }
+        (0..self.height).all(|y| (0..self.width).all(|x| self.cells[self.index(x, y)] == other.cells[other.index(x, y)]))
+    }
+}
+
+impl<T: Clone + Default + Eq> Eq for Grid<T> {}
+
+/// Agrees with `PartialEq`: the dimensions, then the row-major cells.
+impl<T: Clone + Default + Hash> Hash for Grid<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.width, self.height).hash(state);
+        T::hash_slice(self.row_major().as_raw(), state);
+    }
+}
+
+/// Fallible counterpart of `from_raw` for bytes from outside the crate:
+/// `(width, height, cells)` becomes a grid, or a `GridError` if the dimensions
+/// are too large or don't match the number of cells.
+impl TryFrom<(usize, usize, Vec<u8>)> for Grid<u8> {
+    type Error = GridError;
+
+    fn try_from((width, height, cells): (usize, usize, Vec<u8>)) -> Result<Self, Self::Error> {
+        let expected = cell_count(width, height)?;
+        if cells.len() != expected {
+            return Err(GridError::DimensionMismatch {
+                expected,
+                actual: cells.len(),
+            });
+        }
+        Ok(Grid::from_raw(width, height, cells))
+    }
+}
+
+// Reading by value needs `Copy`; the rest of the API only needs `Clone + Default`.
+impl<T: Copy + Default> Grid<T> {
+    /// READS a cell's value at (x, y).
+    /// Safe to call with negative coordinates due to wrapping.
+    pub fn get(&self, x: isize, y: isize) -> T {
+        let idx = self.get_index(x, y);
+        // We use unsafe for maximum speed in production, but safe indexing here for stability.
+        self.cells[idx] 
+    }
+
+    /// Same as `get`, but skips the bounds check on the final slice access.
+    /// Only available with the `unsafe_fast` feature; `get` stays the default.
+    ///
+    /// Safety invariant (upheld internally, so this is safe to call): `get_index`
+    /// wraps both coordinates with `rem_euclid`, giving `x < width` and
+    /// `y < height`, so `index(x, y) < width * height == cells.len()`. An empty
+    /// grid never gets that far, because `rem_euclid` by zero panics first.
+    #[cfg(feature = "unsafe_fast")]
+    #[inline]
+    pub fn get_unchecked(&self, x: isize, y: isize) -> T {
+        let idx = self.get_index(x, y);
+        // SAFETY: `idx < self.cells.len()` by the invariant above.
+        unsafe { *self.cells.get_unchecked(idx) }
+    }
+
+    /// READS a cell's value at (x, y), resolving off-grid coordinates
+    /// according to `boundary` instead of always wrapping.
+    pub fn get_bounded(&self, x: isize, y: isize, boundary: Boundary) -> T {
+        match boundary {
+            Boundary::Torus => self.get(x, y),
+            Boundary::Dead => {
+                let inside = (0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y);
+                if inside {
+                    self.cells[self.index(x as usize, y as usize)]
+                } else {
+                    T::default()
+                }
+            }
+            Boundary::Reflective => {
+                let x = reflect(x, self.width);
+                let y = reflect(y, self.height);
+                self.cells[self.index(x, y)]
+            }
+        }
+    }
+}
+
+/// Folds a coordinate into `0..len` by mirroring at both edges, repeating
+/// every `2 * len` so reads arbitrarily far off-grid still land inside.
+fn reflect(coord: isize, len: usize) -> usize {
+    let period = 2 * len as isize;
+    let folded = coord.rem_euclid(period) as usize;
+    if folded < len {
+        folded
+    } else {
+        period as usize - 1 - folded
+    }
+}
+
+// --- Shared Cell Access ---
+
+/// Toroidal read/write access to a grid of `u8` cells, whatever its storage.
+///
+/// Implemented by the heap `Grid<u8>` and the fixed-size `StackGrid`, so engine
+/// code written against it (`engine::step_cells`) runs on either.
+pub trait CellGrid {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    /// Reads (x, y), wrapping around the edges like `Grid::get`.
+    fn get(&self, x: isize, y: isize) -> u8;
+    /// Writes (x, y). Panics if out of bounds, like `Grid::set`.
+    fn set(&mut self, x: usize, y: usize, value: u8);
+
+    /// Number of Ink (== 1) cells among the 8 Moore neighbors of (x, y).
+    fn live_neighbors(&self, x: isize, y: isize) -> u8 {
+        MOORE_OFFSETS.iter().filter(|(dx, dy)| self.get(x + dx, y + dy) == 1).count() as u8
+    }
+}
+
+impl CellGrid for Grid<u8> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn get(&self, x: isize, y: isize) -> u8 {
+        Grid::get(self, x, y)
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: u8) {
+        Grid::set(self, x, y, value)
+    }
+}
+
+// --- Population & Tiled Hashing ---
+
+impl Grid<u8> {
+    /// The cells as raw bytes, one byte per cell, in storage order. Proof hashes
+    /// always use row-major order, which this matches only for `Layout::RowMajor`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// Counts the live ("Ink") cells, i.e. every non-zero cell.
+    pub fn count_alive(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell != 0).count()
+    }
+
+    /// Counts the live cells of the `w x h` rectangle starting at (x, y), with
+    /// the same wrapping and clamping as `fill_region`: every cell is counted
+    /// at most once, and nothing is copied out. A zero-sized region counts 0.
+    pub fn count_alive_region(&self, x: isize, y: isize, w: usize, h: usize) -> usize {
+        let mut alive = 0;
+        for dy in 0..h.min(self.height) {
+            for dx in 0..w.min(self.width) {
+                alive += usize::from(self.cells[self.get_index(x + dx as isize, y + dy as isize)] != 0);
+            }
+        }
+        alive
+    }
+
+    /// SHA-256 of the logical cells: one byte per cell, row-major, whatever
+    /// the storage. For a row-major grid (every seeded grid) this is exactly
+    /// the digest `hash_grid` hex-encodes; a column-major copy of the same grid,
+    /// or a `PackedGrid` holding the same 0/1 pattern (`PackedGrid::logical_hash`),
+    /// gives the same value. Like `hash_grid`, the dimensions are not included.
+    pub fn logical_hash(&self) -> [u8; 32] {
+        Sha256::digest(self.row_major().as_raw()).into()
+    }
+
+    /// The (x, y) of every live (non-zero) cell, in row-major order whatever the
+    /// layout; `alive_iter().count() == count_alive()`. Scans every cell for
+    /// now, but callers written against it need not care how cells are stored.
+    pub fn alive_iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height).flat_map(move |y| {
+            (0..self.width)
+                .filter(move |&x| self.cells[self.index(x, y)] != 0)
+                .map(move |x| (x, y))
+        })
+    }
+
+    /// Shrinks the grid by `scale` in each dimension (rounding up), keeping the
+    /// maximum of every `scale x scale` block. A block with any Ink stays Ink, so
+    /// small patterns never vanish from a downsampled view. `scale = 1` is a copy.
+    pub fn downsample(&self, scale: usize) -> Grid<u8> {
+        assert!(scale > 0, "Scale must be non-zero");
+
+        let width = self.width.div_ceil(scale);
+        let height = self.height.div_ceil(scale);
+        let mut out: Grid<u8> = Grid::with_layout(width, height, self.layout);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let idx = out.index(x / scale, y / scale);
+                out.cells[idx] = out.cells[idx].max(self.cells[self.index(x, y)]);
+            }
+        }
+        out
+    }
+
+    /// Hashes the grid as a set of `tile_w x tile_h` rectangles (SHA-256 per tile).
+    ///
+    /// Tiles are ordered row-major (left-to-right, then top-to-bottom), so a server
+    /// can challenge the prover for tile `i` and compare a single digest.
+    /// Tiles on the right/bottom edge are clipped when the dimensions are not
+    /// divisible by the tile size; each tile hashes its cells row by row.
+    pub fn tile_hashes(&self, tile_w: usize, tile_h: usize) -> Vec<[u8; 32]> {
+        assert!(tile_w > 0 && tile_h > 0, "Tile dimensions must be non-zero");
+
+        let grid = self.row_major();
+        let mut hashes = Vec::new();
+        for ty in (0..self.height).step_by(tile_h) {
+            for tx in (0..self.width).step_by(tile_w) {
+                let x_end = (tx + tile_w).min(self.width);
+                let y_end = (ty + tile_h).min(self.height);
+
+                let mut hasher = Sha256::new();
+                for y in ty..y_end {
+                    let row = y * self.width;
+                    hasher.update(&grid.cells[row + tx..row + x_end]);
+                }
+                hashes.push(hasher.finalize().into());
+            }
+        }
+        hashes
+    }
+}
+
+// --- Summary Statistics ---
+
+/// Population, density, block entropy and extent of a grid, from `Grid::stats`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridStats {
+    /// Live (non-zero) cells, as `count_alive`.
+    pub alive: usize,
+    /// `alive / (width * height)`, or 0 for an empty grid.
+    pub density: f64,
+    /// Shannon entropy, in bits (0..=4), of the 2x2 blocks anchored at every
+    /// cell (wrapping): 0 for a uniform grid, near 4 for noise.
+    pub entropy: f64,
+    /// Smallest (x, y, width, height) rectangle holding every live cell, on the
+    /// torus: `x + width` may run past the right edge and wrap to column 0
+    /// (likewise for y), when that gives a tighter box. `None` with no live cells.
+    pub bounding_box: Option<(usize, usize, usize, usize)>,
+}
+
+impl Grid<u8> {
+    /// Computes `GridStats` in one pass over the cells.
+    pub fn stats(&self) -> GridStats {
+        let mut alive = 0;
+        let mut blocks = [0usize; 16];
+        let mut columns = vec![false; self.width];
+        let mut rows = vec![false; self.height];
+
+        for (y, row_seen) in rows.iter_mut().enumerate() {
+            for (x, column_seen) in columns.iter_mut().enumerate() {
+                if self.cells[self.index(x, y)] != 0 {
+                    alive += 1;
+                    *column_seen = true;
+                    *row_seen = true;
+                }
+                let (x, y) = (x as isize, y as isize);
+                let block = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &(dx, dy))| self.cells[self.get_index(x + dx, y + dy)] != 0)
+                    .fold(0, |block, (bit, _)| block | 1 << bit);
+                blocks[block] += 1;
+            }
+        }
+
+        let total = self.cells.len();
+        let entropy = blocks
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| count as f64 / total as f64 * (total as f64 / count as f64).log2())
+            .fold(0.0, |sum, bits| sum + bits);
+        let bounding_box = circular_span(&columns)
+            .zip(circular_span(&rows))
+            .map(|((x, width), (y, height))| (x, y, width, height));
+
+        GridStats {
+            alive,
+            density: if total == 0 { 0.0 } else { alive as f64 / total as f64 },
+            entropy,
+            bounding_box,
+        }
+    }
+}
+
+/// The shortest (start, len) arc of a circle of `occupied.len()` slots that
+/// covers every occupied slot: everything but the longest run of empty ones.
+/// Ties go to the lowest start, so a box that fits without wrapping does.
+fn circular_span(occupied: &[bool]) -> Option<(usize, usize)> {
+    let n = occupied.len();
+    let first = occupied.iter().position(|&slot| slot)?;
+
+    // Walk once around from the first occupied slot, measuring each gap.
+    let (mut gap, mut span) = (0, (first, n));
+    let mut run = 0;
+    for i in 1..=n {
+        let slot = (first + i) % n;
+        if !occupied[slot] {
+            run += 1;
+            continue;
+        }
+        if run > gap || (run == gap && run > 0 && slot < span.0) {
+            gap = run;
+            span = (slot, n - run);
+        }
+        run = 0;
+    }
+    Some(span)
+}
+
+// --- Deterministic Fillers (Fixtures & Benchmarks) ---
+//
+// Fixed patterns that need no seed or PRNG, for rule debugging and for
+// measuring tick cost on known inputs. Live cells are 1.
+
+impl Grid<u8> {
+    /// Alternating cells, with ink wherever `x + y` is even: `ceil(w * h / 2)`
+    /// live cells. Every cell has exactly 4 live neighbors on an even-sized
+    /// torus, so the next tick is all-dead.
+    pub fn checkerboard(width: usize, height: usize) -> Grid<u8> {
+        Self::filled_with(width, height, |x, y| (x + y) % 2 == 0)
+    }
+
+    /// Vertical stripes: one live column every `period` columns, starting at
+    /// column 0 (`period = 2` alternates columns, `period = 1` is all ink).
+    ///
+    /// # Panics
+    /// If `period` is 0.
+    pub fn stripes(width: usize, height: usize, period: usize) -> Grid<u8> {
+        assert!(period > 0, "Stripe period must be non-zero");
+        Self::filled_with(width, height, |x, _| x % period == 0)
+    }
+
+    /// An empty grid with a single live cell at (x, y).
+    ///
+    /// # Panics
+    /// If (x, y) is not inside the grid.
+    pub fn single(width: usize, height: usize, x: usize, y: usize) -> Grid<u8> {
+        assert!(x < width && y < height, "Cell ({}, {}) is outside a {}x{} grid", x, y, width, height);
+        let mut grid = Grid::new(width, height);
+        grid.set(x, y, 1);
+        grid
+    }
+
+    fn filled_with(width: usize, height: usize, is_ink: impl Fn(usize, usize) -> bool) -> Grid<u8> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| is_ink(x, y) as u8);
+        Grid::from_iter_sized(width, height, cells).expect("Grid dimensions too large")
+    }
+}
+
+// --- Masking & Compositing ---
+//
+// Binary operations on two same-sized grids. Any non-zero cell counts as set,
+// and results hold only 0s and 1s.
+
+/// How `Grid::combine` merges each pair of cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoolOp {
+    /// Set where both cells are set.
+    And,
+    /// Set where either cell is set.
+    Or,
+    /// Set where exactly one cell is set: a change-map of two states.
+    Xor,
+}
+
+impl BoolOp {
+    #[inline]
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BoolOp::And => a & b,
+            BoolOp::Or => a | b,
+            BoolOp::Xor => a ^ b,
+        }
+    }
+}
+
+impl Grid<u8> {
+    /// Swaps ink and blank: 0 becomes 1 and every non-zero cell becomes 0.
+    pub fn invert(&self) -> Grid<u8> {
+        let cells = self.cells.iter().map(|&cell| (cell == 0) as u8).collect();
+        Grid { width: self.width, height: self.height, layout: self.layout, cells }
+    }
+
+    /// Merges `self` and `other` cell by cell with `op`. The result uses
+    /// `self`'s layout; `other` may use either.
+    ///
+    /// Errors with `GridError::SizeMismatch` unless both grids have the same
+    /// width and height.
+    pub fn combine(&self, other: &Grid<u8>, op: BoolOp) -> Result<Grid<u8>, GridError> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(GridError::SizeMismatch {
+                expected: (self.width, self.height),
+                actual: (other.width, other.height),
+            });
+        }
+
+        let other = if other.layout == self.layout {
+            Cow::Borrowed(other)
+        } else {
+            Cow::Owned(other.clone().into_layout(self.layout))
+        };
+        let cells = self
+            .cells
+            .iter()
+            .zip(&other.cells)
+            .map(|(&a, &b)| op.apply(a != 0, b != 0) as u8)
+            .collect();
+        Ok(Grid { width: self.width, height: self.height, layout: self.layout, cells })
+    }
+}
+
+// --- Translation-Invariant Comparison (Analysis Only) ---
+
+impl Grid<u8> {
+    /// Returns the pattern shifted to a deterministic "canonical" position.
+    ///
+    /// On a torus, a pattern and any translation of it are the same pattern. We pick
+    /// the translation whose row-major byte string is lexicographically smallest.
+    /// This tries all `width * height` offsets, so it costs O((w*h)^2) in the worst
+    /// case (comparisons usually bail out early): meant for deduplicating discovered
+    /// oscillators, never for proofs.
+    pub fn canonical_translation(&self) -> Grid<u8> {
+        let (w, h) = (self.width, self.height);
+        // Cell (x, y) of the candidate with offset (ox, oy) is self(x + ox, y + oy).
+        let cell_at = |ox: usize, oy: usize, i: usize| {
+            let (x, y) = (i % w, i / w);
+            self.cells[self.index((x + ox) % w, (y + oy) % h)]
+        };
+
+        let mut best = (0, 0);
+        for oy in 0..h {
+            for ox in 0..w {
+                let smaller = (0..w * h)
+                    .map(|i| cell_at(ox, oy, i).cmp(&cell_at(best.0, best.1, i)))
+                    .find(|ord| ord.is_ne())
+                    .is_some_and(|ord| ord.is_lt());
+                if smaller {
+                    best = (ox, oy);
+                }
+            }
+        }
+
+        let cells = (0..w * h).map(|i| cell_at(best.0, best.1, i)).collect();
+        Grid::from_raw(w, h, cells)
+    }
+
+    /// True if `other` is a toroidal translation of `self` (same dimensions required).
+    pub fn translation_equal(&self, other: &Grid<u8>) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.canonical_translation() == other.canonical_translation()
+    }
+}
+
+// --- Pattern Search (Analysis Only) ---
+
+impl Grid<u8> {
+    /// Returns every top-left position `(x, y)` where `pattern` matches exactly,
+    /// in row-major order. Matches may wrap across the torus edges, and zero cells
+    /// in the pattern must be zero in the grid too.
+    ///
+    /// Brute force: O(N * M) for N grid cells and M pattern cells, with an early
+    /// exit on the first mismatch. Fine for gliders and logos, not for large
+    /// patterns on large grids. An empty pattern, or one wider or taller than
+    /// the grid, matches nowhere.
+    pub fn find_pattern(&self, pattern: &Grid<u8>) -> Vec<(usize, usize)> {
+        if pattern.cells.is_empty() || pattern.width > self.width || pattern.height > self.height {
+            return Vec::new();
+        }
+
+        let (grid, pattern) = (self.row_major(), pattern.row_major());
+        let matches_at = |x: usize, y: usize| {
+            (0..pattern.height).all(|py| {
+                let row = ((y + py) % grid.height) * grid.width;
+                pattern.cells[py * pattern.width..(py + 1) * pattern.width]
+                    .iter()
+                    .enumerate()
+                    .all(|(px, &cell)| grid.cells[row + (x + px) % grid.width] == cell)
+            })
+        };
+
+        let mut found = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if matches_at(x, y) {
+                    found.push((x, y));
+                }
+            }
+        }
+        found
+    }
+}
+
+// --- Halo Exchange (Distributed / Tiled Proving) ---
+//
+// A huge grid can be split into tiles across workers. To tick its edge cells, a
+// tile needs a one-cell "halo" of its neighbors' border cells. The scheme:
+// 1. Each worker publishes `tile.edges()`.
+// 2. Each worker pads its tile (`with_halo`) and fills the ring with its
+//    neighbors' borders (`apply_halo`): the halo's `top` is the bottom row of the
+//    tile above, its `left` is the right column of the tile to the left, and each
+//    corner comes from the diagonal neighbor.
+// 3. Tick the padded tile normally, then crop it back (`without_halo`).
+//
+// Neighbors are found on the torus of tiles, so the tile "above" the first row of
+// tiles is the last row of tiles: this keeps the global seam wrapping exactly
+// like the undivided grid.
+
+/// The one-cell border of a grid (or a halo assembled from neighbors' borders).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edges {
+    /// First row, left to right (`width` cells).
+    pub top: Vec<u8>,
+    /// Last row, left to right (`width` cells).
+    pub bottom: Vec<u8>,
+    /// First column, top to bottom (`height` cells).
+    pub left: Vec<u8>,
+    /// Last column, top to bottom (`height` cells).
+    pub right: Vec<u8>,
+    /// Corner cells: [top-left, top-right, bottom-left, bottom-right].
+    pub corners: [u8; 4],
+}
+
+impl Grid<u8> {
+    /// Returns the boundary rows/columns of the grid. Panics on an empty grid.
+    pub fn edges(&self) -> Edges {
+        assert!(self.width > 0 && self.height > 0, "An empty grid has no edges");
+        let (w, h) = (self.width, self.height);
+        let cell = |x: usize, y: usize| self.cells[self.index(x, y)];
+
+        Edges {
+            top: (0..w).map(|x| cell(x, 0)).collect(),
+            bottom: (0..w).map(|x| cell(x, h - 1)).collect(),
+            left: (0..h).map(|y| cell(0, y)).collect(),
+            right: (0..h).map(|y| cell(w - 1, y)).collect(),
+            corners: [cell(0, 0), cell(w - 1, 0), cell(0, h - 1), cell(w - 1, h - 1)],
+        }
+    }
+
+    /// Returns a copy padded with a one-cell ring of empty cells, ready for `apply_halo`.
+    pub fn with_halo(&self) -> Grid<u8> {
+        let grid = self.row_major();
+        let mut padded = Grid::new(self.width + 2, self.height + 2);
+        for y in 0..self.height {
+            let src = &grid.cells[y * self.width..(y + 1) * self.width];
+            let dst = (y + 1) * padded.width + 1;
+            padded.cells[dst..dst + self.width].copy_from_slice(src);
+        }
+        padded.into_layout(self.layout)
+    }
+
+    /// Crops the one-cell ring off a padded grid (the inverse of `with_halo`).
+    pub fn without_halo(&self) -> Grid<u8> {
+        assert!(self.width >= 2 && self.height >= 2, "Grid has no halo ring to remove");
+        let (w, h) = (self.width - 2, self.height - 2);
+        let grid = self.row_major();
+        let mut cells = Vec::with_capacity(w * h);
+        for y in 1..=h {
+            cells.extend_from_slice(&grid.cells[y * self.width + 1..y * self.width + 1 + w]);
+        }
+        Grid::from_raw(w, h, cells).into_layout(self.layout)
+    }
+
+    /// Writes `halo` into the outer ring of this padded grid (see `with_halo`).
+    ///
+    /// The padded grid must be `(halo.top.len() + 2) x (halo.left.len() + 2)`:
+    /// `top`/`bottom` fill the first/last row between the corners, `left`/`right`
+    /// the first/last column, and `corners` the four corner cells.
+    pub fn apply_halo(&mut self, halo: &Edges) {
+        let (w, h) = (halo.top.len(), halo.left.len());
+        assert!(
+            self.width == w + 2 && self.height == h + 2
+                && halo.bottom.len() == w && halo.right.len() == h,
+            "Halo does not fit this padded grid"
+        );
+        if self.layout != Layout::RowMajor {
+            let mut row_major = self.clone().into_layout(Layout::RowMajor);
+            row_major.apply_halo(halo);
+            *self = row_major.into_layout(self.layout);
+            return;
+        }
+        let stride = self.width;
+
+        self.cells[1..=w].copy_from_slice(&halo.top);
+        self.cells[(h + 1) * stride + 1..(h + 1) * stride + 1 + w].copy_from_slice(&halo.bottom);
+        for y in 0..h {
+            self.cells[(y + 1) * stride] = halo.left[y];
+            self.cells[(y + 1) * stride + w + 1] = halo.right[y];
+        }
+
+        let [top_left, top_right, bottom_left, bottom_right] = halo.corners;
+        self.cells[0] = top_left;
+        self.cells[w + 1] = top_right;
+        self.cells[(h + 1) * stride] = bottom_left;
+        self.cells[(h + 1) * stride + w + 1] = bottom_right;
+    }
+}
+
+/// Combines tile hashes into a single Merkle root.
+///
+/// Each level hashes adjacent pairs (`SHA-256(left || right)`); an odd node at the
+/// end of a level is carried up unchanged. An empty list hashes to `SHA-256("")`.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return Sha256::digest([]).into();
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().into()
+                }
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
 }
 
 // --- Display Implementation for Debugging ---
+
+/// Cells per dimension shown by `Display` (use `preview(0)` for the full grid).
+const DISPLAY_LIMIT: usize = 64;
+
+/// Default glyph for Ink: U+2588 FULL BLOCK, escaped so the source stays ASCII.
+pub const DEFAULT_ALIVE_GLYPH: char = '\u{2588}';
+
+/// Default glyph for an empty cell.
+pub const DEFAULT_DEAD_GLYPH: char = '.';
+
+/// Appended to preview rows cut short by the width limit: U+2026 HORIZONTAL ELLIPSIS.
+const TRUNCATED_ROW_MARKER: char = '\u{2026}';
+
+impl Grid<u8> {
+    /// Renders every cell, one line per row, with `alive` for non-zero cells and
+    /// `dead` for empty ones (e.g. `'#'` / `' '` for ASCII-only terminals).
+    pub fn render_ascii(&self, alive: char, dead: char) -> String {
+        let mut out = String::new();
+        self.render_rows(self.width, self.height, alive, dead, &mut out);
+        out
+    }
+
+    /// Parses ASCII art in the shape `render_ascii` produces: one line per row,
+    /// `alive` for a live cell (1) and any other character for a dead one.
+    /// The width is the longest line (in characters), shorter lines are padded
+    /// with dead cells, and the height is the line count. For 0/1 grids,
+    /// `from_ascii(&grid.render_ascii(alive, dead), alive)` is `grid` again.
+    ///
+    /// Errors only if the size exceeds `MAX_CELLS`.
+    pub fn from_ascii(text: &str, alive: char) -> Result<Grid<u8>, GridError> {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let cells = lines.iter().flat_map(|line| {
+            let padding = width - line.chars().count();
+            line.chars().map(|c| (c == alive) as u8).chain(std::iter::repeat_n(0, padding))
+        });
+        Grid::from_iter_sized(width, lines.len(), cells)
+    }
+
+    /// Appends the top-left `w x h` cells to `out`, one line per row. Rows
+    /// narrower than the grid end in `TRUNCATED_ROW_MARKER`.
+    fn render_rows(&self, w: usize, h: usize, alive: char, dead: char, out: &mut String) {
+        for y in 0..h {
+            for x in 0..w {
+                let cell = self.cells[self.index(x, y)];
+                out.push(if cell > 0 { alive } else { dead });
+            }
+            if w < self.width {
+                out.push(TRUNCATED_ROW_MARKER);
+            }
+            out.push('\n');
+        }
+    }
+
+    /// Renders the grid as ASCII art, showing at most `limit` cells per dimension.
+    /// A `limit` of 0 means "no truncation" and renders every cell.
+    ///
+    /// Each axis is cut independently, so a 10000x10 grid shows as 64x10 rather
+    /// than being squashed. When anything is cut, the header names the full size,
+    /// every cut row ends in `TRUNCATED_ROW_MARKER`, and a closing note says which
+    /// axis was cut.
+    pub fn preview(&self, limit: usize) -> String {
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        let h = self.height.min(limit);
+        let w = self.width.min(limit);
+        let truncated = w < self.width || h < self.height;
+
+        let mut out = if truncated {
+            format!("Grid Preview ({}x{} of {}x{}):\n", w, h, self.width, self.height)
+        } else {
+            format!("Grid Preview ({}x{}):\n", w, h)
+        };
+        // 0 is Empty (dot), anything else is Ink (block)
+        self.render_rows(w, h, DEFAULT_ALIVE_GLYPH, DEFAULT_DEAD_GLYPH, &mut out);
+
+        if truncated {
+            let mut shown = Vec::new();
+            if w < self.width {
+                shown.push(format!("{} of {} columns", w, self.width));
+            }
+            if h < self.height {
+                shown.push(format!("{} of {} rows", h, self.height));
+            }
+            out.push_str(&format!("... (truncated: {} shown)\n", shown.join(", ")));
+        }
+        out
+    }
+}
+
 // Allows you to print the grid to the console with `println!("{}", grid);`
 // Renders the grid as ASCII art.
 impl fmt::Display for Grid<u8> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Only print a small section if the grid is huge to avoid flooding the console
-        let display_limit = 64; 
-        let h = self.height.min(display_limit);
-        let w = self.width.min(display_limit);
+        f.write_str(&self.preview(DISPLAY_LIMIT))
+    }
+}
 
-        writeln!(f, "Grid Preview ({}x{}):", w, h)?;
-        for y in 0..h {
-            for x in 0..w {
-                let cell = self.cells[y * self.width + x];
-                // 0 is Empty (Space), 1 is Ink (Block)
-                let symbol = if cell > 0 { "█" } else { "." };
-                write!(f, "{}", symbol)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 6x4 grid with a few scattered live cells, for tests that need more than an empty grid.
+    fn sample() -> Grid<u8> {
+        Grid::from_ascii("#....#\n.##...\n...#..\n#....#\n", '#').unwrap()
+    }
+
+    #[test]
+    fn tile_hashes_are_stable_and_local() {
+        let grid = sample();
+        let hashes = grid.tile_hashes(4, 3);
+        assert_eq!(hashes.len(), 4);
+        assert_eq!(hashes, grid.clone().tile_hashes(4, 3));
+        assert_eq!(hashes, grid.clone().into_layout(Layout::ColMajor).tile_hashes(4, 3));
+        assert_eq!(merkle_root(&hashes), merkle_root(&grid.tile_hashes(4, 3)));
+
+        // The top-left tile is its three 4-cell rows, hashed in order.
+        let mut first = Sha256::new();
+        for y in 0..3 {
+            first.update(&grid.as_raw()[y * 6..y * 6 + 4]);
+        }
+        assert_eq!(hashes[0], <[u8; 32]>::from(first.finalize()));
+
+        let mut flipped = grid.clone();
+        flipped.set(5, 3, 0);
+        let changed: Vec<usize> = (0..4).filter(|&i| flipped.tile_hashes(4, 3)[i] != hashes[i]).collect();
+        assert_eq!(changed, vec![3]);
+    }
+
+    #[test]
+    fn double_transpose_is_identity() {
+        let grid = sample();
+        let transposed = grid.transpose();
+        assert_eq!((transposed.width(), transposed.height()), (4, 6));
+        for (x, y) in [(0, 0), (5, 0), (1, 1), (3, 2), (5, 3)] {
+            assert_eq!(transposed.get(y, x), grid.get(x, y));
+        }
+        assert_eq!(transposed.transpose(), grid);
+
+        let mut in_place = grid.clone();
+        in_place.transpose_in_place();
+        assert_eq!(in_place, transposed);
+        in_place.transpose_in_place();
+        assert_eq!(in_place, grid);
+
+        let mut square = Grid::from_raw(3, 3, (0..9).collect::<Vec<u8>>());
+        square.transpose_in_place();
+        assert_eq!(square.as_raw(), &[0, 3, 6, 1, 4, 7, 2, 5, 8]);
+    }
+
+    #[test]
+    fn fill_region_changes_exactly_the_region() {
+        let mut grid: Grid<u8> = Grid::new(10, 8);
+        grid.fill_region(2, 3, 4, 2, 1);
+        assert_eq!(grid.count_alive(), 8);
+        assert_eq!(grid.get(2, 3), 1);
+        assert_eq!(grid.get(5, 4), 1);
+        assert_eq!(grid.get(6, 4), 0);
+
+        // Hanging off the bottom-right corner wraps to the other edges.
+        let mut wrapped: Grid<u8> = Grid::new(10, 8);
+        wrapped.fill_region(8, 7, 3, 2, 1);
+        assert_eq!(wrapped.count_alive(), 6);
+        assert_eq!(wrapped.get(0, 0), 1);
+
+        // Oversized regions write every cell once.
+        let mut all: Grid<u8> = Grid::new(10, 8);
+        all.fill_region(-3, 5, 100, 100, 1);
+        assert_eq!(all.count_alive(), 80);
+    }
+
+    #[test]
+    fn preview_truncates_or_renders_in_full() {
+        let grid = Grid::checkerboard(10, 10);
+
+        let full = grid.preview(0);
+        assert!(full.starts_with("Grid Preview (10x10):\n"));
+        assert_eq!(full.lines().count(), 11);
+        assert!(!full.contains(TRUNCATED_ROW_MARKER));
+        assert_eq!(grid.preview(10), full);
+
+        let cut = grid.preview(4);
+        assert!(cut.starts_with("Grid Preview (4x4 of 10x10):\n"));
+        assert_eq!(cut.lines().count(), 6);
+        assert!(cut.lines().skip(1).take(4).all(|row| row.chars().count() == 5 && row.ends_with(TRUNCATED_ROW_MARKER)));
+        assert!(cut.ends_with("... (truncated: 4 of 10 columns, 4 of 10 rows shown)\n"));
+    }
+
+    #[test]
+    fn string_cells_are_read_by_reference() {
+        let mut grid: Grid<String> = Grid::new(3, 2);
+        grid.set(1, 0, "ink".to_string());
+        assert_eq!(grid.get_ref(1, 0), "ink");
+        assert_eq!(grid.get_ref(4, 2), "ink");
+        assert_eq!(grid.get_ref(-1, -1), "");
+    }
+
+    #[test]
+    fn shifted_copies_share_a_canonical_translation() {
+        let grid = sample();
+        let shifted = grid.shift(2, -1);
+        assert_ne!(shifted, grid);
+        assert!(grid.translation_equal(&shifted));
+        assert_eq!(grid.canonical_translation(), shifted.canonical_translation());
+
+        let mut other = grid.clone();
+        other.set(2, 2, 1);
+        assert!(!grid.translation_equal(&other));
+    }
+
+    #[test]
+    fn halo_exchange_ticks_like_the_whole_grid() {
+        use crate::engine::{generate_grid_from_seed, tick};
+
+        let whole = generate_grid_from_seed("alice", "secret", 12, 10);
+        let half = |x0: usize| {
+            let cells = (0..60).map(|i| whole.get((x0 + i % 6) as isize, (i / 6) as isize));
+            Grid::from_iter_sized(6, 10, cells).unwrap()
+        };
+        let (left, right) = (half(0), half(6));
+
+        // One row of two tiles: each tile is its own neighbor above and below,
+        // and the other tile is its neighbor on both sides.
+        let tick_tile = |tile: &Grid<u8>, other: &Grid<u8>| {
+            let (own, side) = (tile.edges(), other.edges());
+            let [o_tl, o_tr, o_bl, o_br] = side.corners;
+            let halo = Edges {
+                top: own.bottom,
+                bottom: own.top,
+                left: side.right,
+                right: side.left,
+                corners: [o_br, o_bl, o_tr, o_tl],
+            };
+            let mut padded = tile.with_halo();
+            padded.apply_halo(&halo);
+            tick(&padded).without_halo()
+        };
+        let (next_left, next_right) = (tick_tile(&left, &right), tick_tile(&right, &left));
+
+        let expected = tick(&whole);
+        for y in 0..10 {
+            for x in 0..6 {
+                assert_eq!(next_left.get(x, y), expected.get(x, y));
+                assert_eq!(next_right.get(x, y), expected.get(x + 6, y));
+            }
+        }
+        assert_eq!(left.with_halo().without_halo(), left);
+    }
+
+    #[test]
+    fn shifts_wrap_and_compose() {
+        let grid = sample();
+        assert_eq!(grid.shift(6, 0), grid);
+        assert_eq!(grid.shift(-12, 8), grid);
+
+        let shifted = grid.shift(2, -1);
+        for y in 0..4 {
+            for x in 0..6 {
+                assert_eq!(shifted.get(x, y), grid.get(x - 2, y + 1));
             }
-            write!(f, "\n")?;
         }
-        if self.height > display_limit {
-            writeln!(f, "... (truncated)")?;
+        assert_eq!(shifted.shift(3, 5), grid.shift(5, 4));
+        assert_eq!(shifted.shift(-2, 1), grid);
+        assert_eq!(Grid::<u8>::new(0, 3).shift(1, 1), Grid::new(0, 3));
+    }
+
+    #[test]
+    fn downsample_halves_and_keeps_every_pattern() {
+        let grid = sample();
+        assert_eq!(grid.downsample(1), grid);
+
+        let half = grid.downsample(2);
+        assert_eq!((half.width(), half.height()), (3, 2));
+        assert_eq!(half.count_alive(), 6);
+        let corner = Grid::from_ascii("#...\n....\n", '#').unwrap();
+        assert_eq!(corner.downsample(2), Grid::from_ascii("#.\n", '#').unwrap());
+
+        let odd = Grid::from_ascii(".....\n.....\n....#\n", '#').unwrap().downsample(2);
+        assert_eq!((odd.width(), odd.height()), (3, 2));
+        assert_eq!(odd.count_alive(), 1);
+        assert_eq!(odd.get(2, 1), 1);
+    }
+
+    #[test]
+    fn raw_bytes_convert_without_copying() {
+        let cells = vec![0, 1, 1, 0, 0, 1];
+        let ptr = cells.as_ptr();
+        let grid = Grid::try_from((3, 2, cells)).unwrap();
+        assert_eq!(grid.get(1, 0), 1);
+        assert_eq!(grid.as_bytes(), &[0, 1, 1, 0, 0, 1]);
+        let cells = grid.into_raw();
+        assert_eq!(cells.as_ptr(), ptr);
+
+        assert_eq!(
+            Grid::try_from((4, 2, cells)),
+            Err(GridError::DimensionMismatch { expected: 8, actual: 6 })
+        );
+        assert!(matches!(Grid::try_from((usize::MAX, 2, vec![])), Err(GridError::TooLarge { .. })));
+    }
+
+    #[test]
+    #[cfg(feature = "unsafe_fast")]
+    fn unchecked_reads_match_get() {
+        let grid = sample().into_layout(Layout::ColMajor);
+        for y in -5..10 {
+            for x in -7..13 {
+                assert_eq!(grid.get_unchecked(x, y), grid.get(x, y), "({}, {})", x, y);
+            }
         }
-        Ok(())
+    }
+
+    #[test]
+    fn ascii_render_uses_the_chosen_glyphs() {
+        let mut grid = sample();
+        grid.set(2, 0, 7);
+        assert_eq!(grid.render_ascii('@', ' '), "@ @  @\n @@   \n   @  \n@    @\n");
+        assert_eq!(grid.render_ascii('█', DEFAULT_DEAD_GLYPH).lines().count(), 4);
+        assert!(grid.render_ascii('█', '·').lines().all(|row| row.chars().count() == 6));
+        assert_eq!(Grid::new(0, 0).render_ascii('#', '.'), "");
+    }
+
+    #[test]
+    fn patterns_are_found_once_even_across_the_seam() {
+        let domino = Grid::from_ascii("##\n", '#').unwrap();
+        let mut grid: Grid<u8> = Grid::new(7, 5);
+        grid.set(2, 1, 1);
+        grid.set(3, 1, 1);
+        assert_eq!(grid.find_pattern(&domino), vec![(2, 1)]);
+
+        let mut seam: Grid<u8> = Grid::new(7, 5);
+        seam.set(6, 4, 1);
+        seam.set(0, 4, 1);
+        assert_eq!(seam.find_pattern(&domino), vec![(6, 4)]);
+
+        let ell = Grid::from_ascii("#.\n##\n", '#').unwrap();
+        assert!(grid.find_pattern(&ell).is_empty());
+        assert!(grid.find_pattern(&Grid::new(0, 0)).is_empty());
+        assert!(grid.find_pattern(&Grid::new(8, 1)).is_empty());
+    }
+
+    #[test]
+    fn display_cuts_wide_and_tall_grids_on_one_axis() {
+        let wide = Grid::checkerboard(1000, 3).to_string();
+        assert!(wide.starts_with("Grid Preview (64x3 of 1000x3):\n"));
+        assert_eq!(wide.lines().count(), 1 + 3 + 1);
+        let rows: Vec<&str> = wide.lines().skip(1).take(3).collect();
+        assert!(rows.iter().all(|row| row.chars().count() == 65 && row.ends_with(TRUNCATED_ROW_MARKER)));
+        assert!(wide.ends_with("... (truncated: 64 of 1000 columns shown)\n"));
+
+        let tall = Grid::checkerboard(3, 1000).to_string();
+        assert!(tall.starts_with("Grid Preview (3x64 of 3x1000):\n"));
+        assert_eq!(tall.lines().count(), 1 + 64 + 1);
+        assert!(tall.lines().skip(1).take(64).all(|row| row.chars().count() == 3));
+        assert!(!tall.contains(TRUNCATED_ROW_MARKER));
+        assert!(tall.ends_with("... (truncated: 64 of 1000 rows shown)\n"));
+    }
+
+    #[test]
+    fn resized_grows_shrinks_and_keeps_the_overlap() {
+        for layout in [Layout::RowMajor, Layout::ColMajor] {
+            let grid = sample().into_layout(layout);
+            assert_eq!(grid.resized(6, 4), grid);
+
+            let grown = grid.resized(9, 7);
+            assert_eq!((grown.width(), grown.height(), grown.layout()), (9, 7, layout));
+            assert_eq!(grown.count_alive(), grid.count_alive());
+            assert_eq!(grown.resized(6, 4), grid);
+
+            let cropped = grid.resized(3, 2);
+            let expected = Grid::from_ascii("#..\n.##\n", '#').unwrap();
+            assert_eq!(cropped, expected);
+            assert_eq!(grid.resized(0, 4).width(), 0);
+        }
+    }
+
+    #[test]
+    fn reflective_reads_mirror_instead_of_wrapping() {
+        let grid = Grid::from_ascii("#..\n..#\n", '#').unwrap();
+        assert_eq!(grid.get_bounded(-1, 0, Boundary::Reflective), 1);
+        assert_eq!(grid.get_bounded(3, 1, Boundary::Reflective), 1);
+        assert_eq!(grid.get_bounded(-1, 1, Boundary::Reflective), 0);
+        assert_eq!(grid.get_bounded(0, -1, Boundary::Reflective), 1);
+        assert_eq!(grid.get_bounded(0, 2, Boundary::Reflective), 0);
+        assert_eq!(grid.get_bounded(6, 4, Boundary::Reflective), grid.get(0, 0));
+        // The torus sees the opposite edge instead.
+        assert_eq!(grid.get_bounded(-1, 0, Boundary::Torus), 0);
+        assert_eq!(grid.get_bounded(-1, 1, Boundary::Torus), 1);
+    }
+
+    #[test]
+    fn reflective_edges_change_the_dynamics() {
+        use crate::engine::run_simulation_with;
+        use crate::rules::{Neighborhood, Rule};
+
+        // A domino on the left edge: the torus starves it, while the mirror
+        // reflects it into a 2x2 block, which is stable.
+        let domino = Grid::from_ascii(".....\n#....\n#....\n.....\n", '#').unwrap();
+        let run = |boundary| run_simulation_with(domino.clone(), 3, &Rule::INK, Neighborhood::Moore, boundary);
+        assert_eq!(run(Boundary::Torus), Grid::new(5, 4));
+        assert_eq!(run(Boundary::Dead), Grid::new(5, 4));
+        assert_eq!(run(Boundary::Reflective), domino);
+    }
+
+    #[test]
+    fn patterns_have_the_documented_shape() {
+        assert_eq!(Grid::checkerboard(6, 4).count_alive(), 12);
+        assert_eq!(Grid::checkerboard(5, 3).count_alive(), 8);
+        assert_eq!(Grid::checkerboard(3, 2).render_ascii('#', '.'), "#.#\n.#.\n");
+        assert_eq!(crate::engine::tick(&Grid::checkerboard(6, 4)).count_alive(), 0);
+
+        let single = Grid::single(7, 5, 6, 4);
+        assert_eq!(single.count_alive(), 1);
+        assert_eq!(single.get(-1, -1), 1);
+
+        assert_eq!(Grid::stripes(7, 2, 3).render_ascii('#', '.'), "#..#..#\n#..#..#\n");
+        assert_eq!(Grid::stripes(4, 3, 1).count_alive(), 12);
+    }
+
+    #[test]
+    fn equal_grids_collapse_in_a_hash_set() {
+        let mut seen = std::collections::HashSet::new();
+        assert!(seen.insert(sample()));
+        assert!(!seen.insert(sample()));
+        assert_eq!(seen.len(), 1);
+        assert!(seen.insert(sample().shift(1, 0)));
+        assert_eq!(seen.len(), 2);
+
+        // The layout is storage, not state: a column-major copy is the same grid.
+        let col_major = sample().into_layout(Layout::ColMajor);
+        assert_eq!(col_major, sample());
+        assert!(!seen.insert(col_major.clone()));
+        assert_ne!(col_major, sample().shift(0, 1));
+        // Same cells, other shape.
+        assert_ne!(Grid::from_raw(2, 3, vec![1u8, 0, 0, 1, 1, 0]), Grid::from_raw(3, 2, vec![1u8, 0, 0, 1, 1, 0]));
+    }
+
+    #[test]
+    fn column_major_reads_the_transposed_data_as_the_same_pattern() {
+        let grid = sample();
+        // The row-major cells of the transpose are exactly the column-major cells of the original.
+        let col_major = Grid { width: 6, height: 4, layout: Layout::ColMajor, cells: grid.transpose().cells };
+        for y in -4..8 {
+            for x in -6..12 {
+                assert_eq!(col_major.get(x, y), grid.get(x, y), "({}, {})", x, y);
+            }
+        }
+        let next = crate::engine::tick(&col_major);
+        assert_eq!(next.layout(), Layout::ColMajor);
+        assert_eq!(next, crate::engine::tick(&grid));
+    }
+
+    #[test]
+    fn invert_and_combine_are_cellwise_boolean_ops() {
+        let grid = sample();
+        assert_eq!(grid.invert().invert(), grid);
+        assert_eq!(grid.invert().count_alive(), 24 - grid.count_alive());
+
+        let xor = grid.combine(&grid, BoolOp::Xor).unwrap();
+        assert_eq!(xor.count_alive(), 0);
+        assert_eq!(xor, Grid::new(6, 4));
+        assert_eq!(grid.combine(&grid.invert(), BoolOp::And).unwrap().count_alive(), 0);
+        assert_eq!(grid.combine(&grid.invert(), BoolOp::Or).unwrap().count_alive(), 24);
+        assert_eq!(grid.combine(&grid.clone().into_layout(Layout::ColMajor), BoolOp::Xor), Ok(xor));
+
+        assert_eq!(
+            grid.combine(&Grid::new(4, 6), BoolOp::And),
+            Err(GridError::SizeMismatch { expected: (6, 4), actual: (4, 6) })
+        );
+    }
+
+    #[test]
+    fn sized_iterators_must_fill_the_grid_exactly() {
+        let grid = Grid::from_iter_sized(6, 4, sample().as_raw().iter().copied()).unwrap();
+        assert_eq!(grid, sample());
+        assert_eq!(Grid::from_iter_sized(3, 2, (0..6).map(|i| i * 10)).unwrap().get(2, 1), 50);
+
+        let short = Grid::<u8>::from_iter_sized(3, 2, [1; 5]);
+        assert_eq!(short, Err(GridError::DimensionMismatch { expected: 6, actual: 5 }));
+        let long = Grid::<u8>::from_iter_sized(3, 2, std::iter::repeat(1));
+        assert_eq!(long, Err(GridError::DimensionMismatch { expected: 6, actual: 7 }));
+        let huge = Grid::<u8>::from_iter_sized(usize::MAX, 2, []);
+        assert_eq!(huge, Err(GridError::TooLarge { width: usize::MAX, height: 2 }));
+    }
+
+    #[test]
+    fn alive_iter_lists_live_cells_in_row_major_order() {
+        let grid = sample();
+        let alive: Vec<_> = grid.alive_iter().collect();
+        assert_eq!(alive, [(0, 0), (5, 0), (1, 1), (2, 1), (3, 2), (0, 3), (5, 3)]);
+        assert_eq!(grid.alive_iter().count(), grid.count_alive());
+        assert!(grid.clone().into_layout(Layout::ColMajor).alive_iter().eq(alive));
+        assert_eq!(Grid::new(5, 5).alive_iter().count(), 0);
+    }
+
+    #[test]
+    fn ascii_art_round_trips_and_pads_short_lines() {
+        let grid = crate::engine::generate_grid_from_seed("alice", "secret", 17, 9);
+        assert_eq!(Grid::from_ascii(&grid.render_ascii('@', ' '), '@').unwrap(), grid);
+        assert_eq!(Grid::from_ascii(&sample().render_ascii('#', '.'), '#').unwrap(), sample());
+
+        let ragged = Grid::from_ascii("x\nxxx\n\nx.x", 'x').unwrap();
+        assert_eq!((ragged.width(), ragged.height()), (3, 4));
+        assert_eq!(ragged.render_ascii('x', '.'), "x..\nxxx\n...\nx.x\n");
+        assert_eq!(Grid::from_ascii("", '#').unwrap(), Grid::new(0, 0));
+    }
+
+    #[test]
+    fn stats_of_empty_full_and_single_cell_grids() {
+        let empty = Grid::<u8>::new(5, 4).stats();
+        assert_eq!(empty, GridStats { alive: 0, density: 0.0, entropy: 0.0, bounding_box: None });
+
+        let full = Grid::new(5, 4).invert().stats();
+        assert_eq!(full, GridStats { alive: 20, density: 1.0, entropy: 0.0, bounding_box: Some((0, 0, 5, 4)) });
+
+        let single = Grid::single(5, 4, 3, 2).stats();
+        assert_eq!((single.alive, single.density), (1, 0.05));
+        assert_eq!(single.bounding_box, Some((3, 2, 1, 1)));
+        // Four blocks each hold the cell in a different corner; the other 16 are empty.
+        let expected = 0.8 * (1.25f64).log2() + 4.0 * 0.05 * 20f64.log2();
+        assert!((single.entropy - expected).abs() < 1e-12, "{}", single.entropy);
+
+        // Cells at both ends of a row box across the seam.
+        let seam = Grid::from_ascii("#....#\n......\n", '#').unwrap().stats();
+        assert_eq!(seam.bounding_box, Some((5, 0, 2, 1)));
+        assert_eq!(Grid::<u8>::new(0, 0).stats(), empty);
+    }
+
+    #[test]
+    fn region_counts_wrap_clamp_and_allow_empty_regions() {
+        let grid = sample();
+        assert_eq!(grid.count_alive_region(1, 1, 3, 2), 3);
+        assert_eq!(grid.count_alive_region(0, 0, 6, 4), 7);
+        // The four corners meet across both seams.
+        assert_eq!(grid.count_alive_region(5, 3, 2, 2), 4);
+        assert_eq!(grid.count_alive_region(-1, -1, 2, 2), 4);
+        // Oversized regions count each cell once.
+        assert_eq!(grid.count_alive_region(-2, 7, 100, 100), 7);
+        assert_eq!(grid.count_alive_region(2, 2, 0, 3), 0);
+        assert_eq!(grid.count_alive_region(2, 2, 3, 0), 0);
+        assert_eq!(grid.clone().into_layout(Layout::ColMajor).count_alive_region(5, 3, 2, 2), 4);
     }
 }
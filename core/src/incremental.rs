@@ -0,0 +1,168 @@
+use crate::grid::{Grid, Layout};
+use crate::rules::{Neighborhood, Rule, MOORE_OFFSETS};
+
+// --- Incremental Simulation (Sparse / Mostly-Static Grids) ---
+//
+// `tick` recomputes every cell every generation. Once a grid settles into
+// still lifes and a few oscillators, almost none of that work changes
+// anything: a cell can only change if it, or one of its neighbors, changed on
+// the previous tick. This engine keeps a live-neighbor count per cell plus the
+// set of cells worth re-evaluating, and only touches those.
+//
+// Results are identical to `run_simulation` (Moore neighborhood, torus): the
+// first tick evaluates every cell, and after that a cell outside the dirty set
+// has the same state and neighbor count as last tick, so the rule would leave
+// it unchanged anyway. On a dense, chaotic grid the bookkeeping costs more
+// than it saves; proofs keep using `run_simulation`.
+
+/// A simulation that only re-evaluates cells near last tick's changes.
+#[derive(Clone, Debug)]
+pub struct IncrementalSim {
+    grid: Grid<u8>,
+    rule: Rule,
+    /// Live (== 1) Moore neighbors of every cell, kept in sync with `grid`.
+    counts: Vec<u8>,
+    /// Cells to evaluate on the next tick, each listed once.
+    dirty: Vec<usize>,
+    /// `queued[i]` is true while cell `i` is in `dirty`.
+    queued: Vec<bool>,
+    /// Scratch list of (cell, new state), reused across ticks.
+    changes: Vec<(usize, u8)>,
+}
+
+impl IncrementalSim {
+    /// Starts from `grid`; the first tick evaluates every cell. The engine
+    /// indexes cells row-major, so a column-major grid is converted on the way in.
+    pub fn new(grid: Grid<u8>, rule: Rule) -> Self {
+        let grid = grid.into_layout(Layout::RowMajor);
+        let mut counts = Vec::new();
+        grid.neighbor_counts_into(&mut counts, Neighborhood::Moore);
+        let cells = counts.len();
+
+        IncrementalSim {
+            grid,
+            rule,
+            counts,
+            dirty: (0..cells).collect(),
+            queued: vec![true; cells],
+            changes: Vec::new(),
+        }
+    }
+
+    /// The current generation.
+    pub fn grid(&self) -> &Grid<u8> {
+        &self.grid
+    }
+
+    /// Consumes the simulation, returning the current generation.
+    pub fn into_grid(self) -> Grid<u8> {
+        self.grid
+    }
+
+    /// Cells the next tick will evaluate (the whole grid before the first tick,
+    /// 0 once the grid is completely static).
+    pub fn active_cells(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// Advances one generation.
+    pub fn tick(&mut self) {
+        // 1. Evaluate every dirty cell against the counts of the current generation.
+        self.changes.clear();
+        for &index in &self.dirty {
+            self.queued[index] = false;
+            let state = self.grid.as_raw()[index];
+            let next = self.rule.apply(state, self.counts[index]);
+            if next != state {
+                self.changes.push((index, next));
+            }
+        }
+        self.dirty.clear();
+
+        // 2. Apply the changes, fixing up the counts around each one and
+        //    queueing the changed cell and its neighbors for the next tick.
+        let width = self.grid.width();
+        let height = self.grid.height();
+        for i in 0..self.changes.len() {
+            let (index, next) = self.changes[i];
+            let previous = std::mem::replace(&mut self.grid.as_raw_mut()[index], next);
+            self.queue(index);
+
+            let was_ink = previous == 1;
+            let is_ink = next == 1;
+            if was_ink == is_ink {
+                continue;
+            }
+            let (x, y) = ((index % width) as isize, (index / width) as isize);
+            for (dx, dy) in MOORE_OFFSETS.iter() {
+                let nx = (x + dx).rem_euclid(width as isize) as usize;
+                let ny = (y + dy).rem_euclid(height as isize) as usize;
+                let neighbor = ny * width + nx;
+                if is_ink {
+                    self.counts[neighbor] += 1;
+                } else {
+                    self.counts[neighbor] -= 1;
+                }
+                self.queue(neighbor);
+            }
+        }
+    }
+
+    /// Advances `steps` generations.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.tick();
+        }
+    }
+
+    fn queue(&mut self, index: usize) {
+        if !self.queued[index] {
+            self.queued[index] = true;
+            self.dirty.push(index);
+        }
+    }
+}
+
+/// `run_simulation` on the incremental engine: same final grid, much less work
+/// once most of the grid has stopped changing.
+pub fn run_simulation_incremental(grid: Grid<u8>, steps: usize) -> Grid<u8> {
+    let mut sim = IncrementalSim::new(grid, Rule::INK);
+    sim.run(steps);
+    sim.into_grid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::{generate_grid_from_seed, run_simulation, step};
+
+    #[test]
+    fn incremental_engine_matches_full_ticks_for_200_steps() {
+        let rules = [Rule::INK, Rule::new(&[3, 6], &[2, 3])];
+        for (user, width, height) in [("alice", 40, 30), ("bob", 17, 23), ("carol", 64, 64), ("dave", 3, 5)] {
+            for rule in rules {
+                let mut full = generate_grid_from_seed(user, "secret", width, height);
+                let mut sim = IncrementalSim::new(full.clone(), rule);
+                for tick in 0..200 {
+                    sim.tick();
+                    full = step(&full, &rule);
+                    assert_eq!(sim.grid(), &full, "{} {}x{} tick {}", user, width, height, tick);
+                }
+            }
+        }
+
+        let seed = generate_grid_from_seed("erin", "secret", 50, 50).into_layout(Layout::ColMajor);
+        assert_eq!(run_simulation_incremental(seed.clone(), 200), run_simulation(seed, 200));
+    }
+
+    #[test]
+    fn static_grids_go_quiet() {
+        let block = Grid::from_ascii("....\n.##.\n.##.\n....\n", '#').unwrap();
+        let mut sim = IncrementalSim::new(block.clone(), Rule::INK);
+        assert_eq!(sim.active_cells(), 16);
+        sim.run(2);
+        assert_eq!(sim.active_cells(), 0);
+        assert_eq!(sim.into_grid(), block);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::engine::{derive_seed, generate_grid_from_seed, hash_grid, step, DetRng};
+use crate::grid::Grid;
+use crate::rules::Rule;
+use sha2::{Digest, Sha256};
+
+// --- Seed-Dependent Jitter (Optional Proof Mode) ---
+//
+// A plain proof is the same B3/S23 tick, thousands of times. That regularity
+// is exactly what fixed-function hardware is good at. In jittered mode every
+// tick draws its rule from a PRNG keyed by the credentials, one of
+// `JITTER_RULES`. These are close Life relatives whose birth and survival
+// thresholds differ only at neighbor counts that rarely occur (6 and 8). All
+// are chaotic rather than dying out, and every one costs the same per cell.
+// Only the rule sequence is jittered.
+//
+// Two other perturbations were tried and dropped. Weighting one neighbor
+// shifts counts upward often enough that grids die out within a few hundred
+// ticks. Offsetting each tick's counting window by up to one cell adds
+// nothing: a tick commutes with a torus shift, so the per-tick offsets add up
+// to one shift of the final grid.
+//
+// Circuitry hard-wired for B3/S23 alone cannot run this schedule; it has to
+// handle every rule in `JITTER_RULES`, switching tick by tick. The schedule's
+// PRNG is seeded from a digest separate from the grid seed, so it tells
+// nothing about the grid. From the credentials, everything is reproducible: a
+// verifier rebuilds the same schedule and re-runs it.
+
+/// The rules a jittered tick chooses between, in `Rule::new` form:
+/// B3/S23 (Life), B36/S23 (HighLife), B38/S23 (Pedestrian Life), B3/S238.
+pub const JITTER_RULES: [(&[u8], &[u8]); 4] = [
+    (&[3], &[2, 3]),
+    (&[3, 6], &[2, 3]),
+    (&[3, 8], &[2, 3]),
+    (&[3], &[2, 3, 8]),
+];
+
+/// Label mixed into the master hash to key the schedule PRNG, so it
+/// never shares a stream with the grid seed.
+const JITTER_DOMAIN: &[u8] = b"inkverify-jitter-v1";
+
+/// The endless per-tick rule schedule for one credential pair (see the notes above).
+#[derive(Clone, Debug)]
+pub struct JitterSchedule {
+    rng: DetRng,
+    rules: [Rule; 4],
+}
+
+impl JitterSchedule {
+    /// The schedule for `username` / `password`. The same credentials always
+    /// give the same schedule.
+    pub fn new(username: &str, password: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(JITTER_DOMAIN);
+        hasher.update(derive_seed(username, password, 1));
+        JitterSchedule {
+            rng: DetRng::from_bytes(&hasher.finalize()),
+            rules: JITTER_RULES.map(|(birth, survival)| Rule::new(birth, survival)),
+        }
+    }
+}
+
+impl Iterator for JitterSchedule {
+    type Item = Rule;
+
+    fn next(&mut self) -> Option<Rule> {
+        Some(self.rules[(self.rng.next_u32() & 3) as usize])
+    }
+}
+
+/// Runs `steps` ticks of `grid`, taking each tick's rule from `schedule`.
+pub fn run_simulation_jittered(mut grid: Grid<u8>, steps: usize, schedule: JitterSchedule) -> Grid<u8> {
+    for rule in schedule.take(steps) {
+        grid = step(&grid, &rule);
+    }
+    grid
+}
+
+/// The jittered counterpart of the standard proof: seeds as usual, runs the
+/// credentials' own schedule, and returns the hex SHA-256 of the final grid.
+/// A different proof from `run_simulation` with the same inputs; a verifier
+/// calls this again with the same arguments and compares.
+pub fn prove_jittered(username: &str, password: &str, width: usize, height: usize, steps: usize) -> String {
+    let grid = generate_grid_from_seed(username, password, width, height);
+    let final_grid = run_simulation_jittered(grid, steps, JitterSchedule::new(username, password));
+    hash_grid(&final_grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedules_reproduce_and_differ_by_credentials() {
+        let schedule = |username, password| JitterSchedule::new(username, password).take(64).collect::<Vec<_>>();
+        let alice = schedule("alice", "secret");
+        assert_eq!(alice, schedule("alice", "secret"));
+        assert_ne!(alice, schedule("alice", "secret2"));
+        assert_ne!(alice, schedule("bob", "secret"));
+
+        // Over 64 ticks every rule shows up, and nothing else does.
+        let rules: Vec<Rule> = JITTER_RULES.iter().map(|&(birth, survival)| Rule::new(birth, survival)).collect();
+        assert!(rules.iter().all(|rule| alice.contains(rule)));
+        assert!(alice.iter().all(|rule| rules.contains(rule)));
+    }
+
+    #[test]
+    fn jittered_proofs_are_reproducible_and_distinct() {
+        let proof = prove_jittered("alice", "secret", 24, 24, 40);
+        assert_eq!(proof, prove_jittered("alice", "secret", 24, 24, 40));
+        assert_ne!(proof, prove_jittered("alice", "wrong", 24, 24, 40));
+        let seed = generate_grid_from_seed("alice", "secret", 24, 24);
+        assert_ne!(proof, hash_grid(&crate::engine::run_simulation(seed.clone(), 40)));
+        assert_eq!(prove_jittered("alice", "secret", 24, 24, 0), hash_grid(&seed));
+
+        // Exactly the scheduled rules, one plain `step` each.
+        let rules: Vec<Rule> = JitterSchedule::new("alice", "secret").take(40).collect();
+        let stepped = rules.iter().fold(seed, |grid, rule| step(&grid, rule));
+        assert_eq!(proof, hash_grid(&stepped));
+    }
+}
@@ -1,34 +1,322 @@
 pub mod grid;
 pub mod engine;
+pub mod rules;
+pub mod builder;
+pub mod packed;
+pub mod password;
+pub mod proof;
+pub mod test_vectors;
+pub mod automaton;
+pub mod challenge;
+pub mod diffusion;
+pub mod incremental;
+pub mod jitter;
+pub mod life_rle;
+pub mod ppm;
+pub mod protocol;
+pub mod stack;
+pub mod storage;
 
 use wasm_bindgen::prelude::*;
-use sha2::{Sha256, Digest};
-pub use grid::Grid;
+pub use automaton::{run_automaton, CellAutomaton, LifeAutomaton};
+pub use builder::SimulationBuilder;
+pub use challenge::{Challenge, Solution};
+pub use diffusion::{diffuse_step, run_diffusion};
+pub use grid::{merkle_root, BoolOp, Boundary, CellGrid, Edges, Grid, GridError, GridStats, Layout};
+pub use incremental::{run_simulation_incremental, IncrementalSim};
+pub use jitter::{prove_jittered, run_simulation_jittered, JitterSchedule};
+pub use life_rle::LifeRleError;
+pub use packed::PackedGrid;
 
 // Re-exports
-pub use engine::{generate_grid_from_seed, run_simulation};
+pub use engine::{
+    benchmark_probe, bounded_generations, calibrate_steps, detect_period, estimate_runtime, DetRng,
+    derive_seed, estimate_footprint, generate_grid_droplets, generate_grid_from_seed,
+    generate_grid_gradient, generate_grid_stretched, generate_grid_warmed,
+    generate_grid_with_endian, generate_grid_with_nonce, generations, Generations, hash_grid,
+    hash_grid_streaming, hash_grid_with_context, leading_zero_bits, params_for_difficulty,
+    permutation, prove_with_target, run_path_bound, recommended_params, replay_range,
+    run_simulation, run_simulation_double_buffered, run_simulation_extinction, run_simulation_hash,
+    run_simulation_metered, run_simulation_population, run_simulation_rules, run_simulation_timed,
+    run_simulation_timeout, run_simulation_until, run_simulation_until_checked, run_vdf,
+    search_nonce_range, simulate_and_hash_streaming, step, step_cells, tick, tick_weighted,
+    CheckInterval, CheckSchedule, HashAlgo, Permutation, RunOutcome, SeedEndian, SimMetrics, StorageKind,
+    try_generate_grid_droplets, try_generate_grid_from_seed, try_generate_grid_gradient,
+    try_generate_grid_warmed, try_generate_grid_with_endian, try_generate_grid_with_nonce,
+};
+pub use password::estimate_password_bits;
+pub use ppm::{encode_ppm, encode_ppm_as, encode_ppm_labels, encode_ppm_with, palette_color, AliveTest};
+pub use proof::{
+    format_proof, parse_proof, verify_artifact, verify_artifact_with_context, verify_artifact_with_limits,
+    verify_proof, verify_proof_with_context, verify_stream, verify_stream_with_limits, verify_streaming,
+    verify_streaming_with_context, ProofArtifact, ProofError, ProofParams, VerifyLimits,
+};
+pub use protocol::{commit, verify_reveal};
+pub use rules::{Neighborhood, Rule, RuleTable, WeightedNeighborhood};
+pub use stack::StackGrid;
+pub use storage::{Storage, DEFAULT_MAX_READ_CELLS};
+pub use test_vectors::{benchmark_vectors, known_vectors, TestVector, VectorBenchmark};
 
 // --- WASM INTERFACE ---
 // Everything below this line is for the Browser
 
+// We use a fixed size (e.g., 500x500) for web challenges to ensure
+// it runs in <1 second on most laptops. Pages that would rather size the
+// proof to the device use `web_benchmark` + `web_params` + `prove_work_full`.
+const WEB_WIDTH: usize = 500;
+const WEB_HEIGHT: usize = 500;
+
 #[wasm_bindgen]
 pub fn prove_work(username: &str, password: &str, steps: usize) -> String {
     // 1. Generate the Grid (Memory Hard step)
-    // We use a fixed size (e.g., 500x500) for web challenges to ensure
-    // it runs in <1 second on most laptops.
-    let width = 500;
-    let height = 500;
-    
-    let initial_grid = generate_grid_from_seed(username, password, width, height);
+    let initial_grid = generate_grid_from_seed(username, password, WEB_WIDTH, WEB_HEIGHT);
 
     // 2. Run the Simulation (CPU/Memory Bandwidth step)
     let final_grid = run_simulation(initial_grid, steps);
 
     // 3. Hash the result
     // We return the Hex String so JavaScript can send it to the server.
-    let mut hasher = Sha256::new();
-    hasher.update(final_grid.as_raw());
-    let result = hasher.finalize();
-    
-    hex::encode(result)
+    hash_grid(&final_grid)
+}
+
+/// Same as `prove_work`, but binds the proof to a server-provided `context`
+/// (a challenge or session id) that is hashed after the grid bytes.
+/// An empty context gives the same hash as `prove_work`.
+#[wasm_bindgen]
+pub fn prove_work_ctx(username: &str, password: &str, steps: usize, context: &[u8]) -> String {
+    let initial_grid = generate_grid_from_seed(username, password, WEB_WIDTH, WEB_HEIGHT);
+    let final_grid = run_simulation(initial_grid, steps);
+    hash_grid_with_context(&final_grid, context)
+}
+
+/// Same proof as `prove_work`, but returns the final grid as PPM (P3) bytes
+/// (black ink on white) so the page can display the visual proof.
+#[wasm_bindgen]
+pub fn prove_work_ppm(username: &str, password: &str, steps: usize) -> Vec<u8> {
+    let initial_grid = generate_grid_from_seed(username, password, WEB_WIDTH, WEB_HEIGHT);
+    let final_grid = run_simulation(initial_grid, steps);
+    encode_ppm(&final_grid, ppm::INK_BLACK, ppm::PAPER_WHITE)
+}
+
+/// Everything the browser needs to show "settled into N ink cells over M steps".
+/// `getter_with_clone` exposes each field to JS as a named getter.
+#[wasm_bindgen(getter_with_clone)]
+pub struct ProofDetails {
+    pub hash: String,
+    pub final_population: usize,
+    pub steps_run: usize,
+}
+
+#[wasm_bindgen]
+pub fn prove_work_detailed(username: &str, password: &str, steps: usize) -> ProofDetails {
+    let initial_grid = generate_grid_from_seed(username, password, WEB_WIDTH, WEB_HEIGHT);
+    let final_grid = run_simulation(initial_grid, steps);
+
+    ProofDetails {
+        hash: hash_grid(&final_grid),
+        final_population: final_grid.count_alive(),
+        steps_run: steps,
+    }
+}
+
+/// Roughly how many progress callbacks a proof makes, so JS isn't flooded.
+const PROGRESS_UPDATES: usize = 100;
+
+/// Same proof as `prove_work`, but calls `callback(step)` every few ticks (about
+/// `PROGRESS_UPDATES` times in all) so the page can drive a progress bar.
+///
+/// The callback only receives the step count and its return value is ignored, so
+/// it cannot influence the result. If it throws, proving stops and the exception
+/// is re-thrown to the caller.
+#[wasm_bindgen]
+pub fn prove_work_with_callback(
+    username: &str,
+    password: &str,
+    steps: usize,
+    callback: &js_sys::Function,
+) -> Result<String, JsValue> {
+    let grid = generate_grid_from_seed(username, password, WEB_WIDTH, WEB_HEIGHT);
+    // A fixed tick count, not `CheckInterval::Adaptive`: wasm32 has no clock.
+    let checks = CheckInterval::Every((steps / PROGRESS_UPDATES).max(1));
+    let grid = run_with_progress(grid, steps, checks, |done| {
+        callback.call1(&JsValue::NULL, &JsValue::from_f64(done as f64)).map(drop)
+    })?;
+    Ok(hash_grid(&grid))
+}
+
+/// The loop behind `prove_work_with_callback`, kept free of JS values so it can
+/// be tested natively: runs `steps` ticks, calling `report(done)` whenever
+/// `checks` comes due and after the last tick, and stopping at the first error.
+fn run_with_progress<E>(
+    mut grid: Grid<u8>,
+    steps: usize,
+    checks: CheckInterval,
+    mut report: impl FnMut(usize) -> Result<(), E>,
+) -> Result<Grid<u8>, E> {
+    let mut checks = checks.schedule();
+    // Tick 0 is always due, and there is no progress to report yet.
+    checks.due(0);
+    for done in 1..=steps {
+        grid = tick(&grid);
+        if done == steps || checks.due(done) {
+            report(done)?;
+        }
+    }
+    Ok(grid)
+}
+
+/// A finished proof plus the final grid, so one call gives the page everything
+/// it needs to both display and submit the result.
+#[wasm_bindgen]
+pub struct ProofResult {
+    hash: String,
+    width: usize,
+    height: usize,
+    steps: usize,
+    grid: Grid<u8>,
+}
+
+#[wasm_bindgen]
+impl ProofResult {
+    /// Hex SHA-256 of the final grid (the value to send to the server).
+    pub fn hash(&self) -> String {
+        self.hash.clone()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The final grid, one byte per cell in row-major order (1 = ink).
+    /// Each call returns a fresh `Uint8Array` copy, so JS never holds a view
+    /// into WASM memory that outlives (or is invalidated by) this object.
+    pub fn pixels(&self) -> Vec<u8> {
+        self.grid.row_major().as_raw().to_vec()
+    }
+}
+
+/// Runs a proof at a caller-chosen size and returns hash, dimensions, step
+/// count, and final cells together. The hash matches the CLI for the same inputs.
+/// Throws (instead of aborting the module) if the size is too large.
+#[wasm_bindgen]
+pub fn prove_work_full(
+    username: &str,
+    password: &str,
+    width: usize,
+    height: usize,
+    steps: usize,
+) -> Result<ProofResult, JsValue> {
+    let initial_grid = try_generate_grid_from_seed(username, password, width, height)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let final_grid = run_simulation(initial_grid, steps);
+
+    Ok(ProofResult {
+        hash: hash_grid(&final_grid),
+        width,
+        height,
+        steps,
+        grid: final_grid,
+    })
+}
+
+/// Grid size and step count for a web proof (see `web_params`).
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct WebParams {
+    pub width: usize,
+    pub height: usize,
+    pub steps: usize,
+}
+
+/// Runs the fixed `benchmark_probe` workload. Time this call with
+/// `performance.now()` and pass the milliseconds to `web_params`.
+#[wasm_bindgen]
+pub fn web_benchmark() -> usize {
+    benchmark_probe()
+}
+
+/// Parameters for a ~1 second proof on this device, from a `web_benchmark`
+/// time (see `recommended_params`). Feed them to `prove_work_full`; the same
+/// input always gives the same parameters.
+#[wasm_bindgen]
+pub fn web_params(benchmark_ms: f64) -> WebParams {
+    let (width, height, steps) = recommended_params(benchmark_ms);
+    WebParams { width, height, steps }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Natively, through the same functions; tests/wasm.rs drives the exports
+    // with real JS values under `wasm-bindgen-test`.
+
+    #[test]
+    fn detailed_proof_reports_population() {
+        let details = prove_work_detailed("alice", "secret", 5);
+        let final_grid = run_simulation(generate_grid_from_seed("alice", "secret", WEB_WIDTH, WEB_HEIGHT), 5);
+
+        assert_eq!(details.hash, prove_work("alice", "secret", 5));
+        assert_eq!(details.final_population, final_grid.count_alive());
+        assert_eq!(details.steps_run, 5);
+        assert!(details.final_population > 0);
+    }
+
+    #[test]
+    fn progress_reaches_every_step_and_keeps_the_proof() {
+        let grid = generate_grid_from_seed("alice", "secret", 16, 16);
+        let mut reports = Vec::new();
+        let final_grid = run_with_progress(grid.clone(), 1005, CheckInterval::Every(10), |done| {
+            reports.push(done);
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+
+        assert_eq!(final_grid, run_simulation(grid.clone(), 1005));
+        assert_eq!(reports.len(), 101);
+        assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(reports.last(), Some(&1005));
+
+        // Adaptive: one report after the first (timed) tick, then none until
+        // the hour-long target, except the final one.
+        let mut reports = Vec::new();
+        let hourly = CheckInterval::Adaptive { target: std::time::Duration::from_secs(3600) };
+        run_with_progress(grid, 300, hourly, |done| {
+            reports.push(done);
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+        assert_eq!(reports, [1, 300]);
+    }
+
+    #[test]
+    fn progress_stops_at_the_first_error() {
+        let grid = generate_grid_from_seed("alice", "secret", 8, 8);
+        let mut calls = 0;
+        let result = run_with_progress(grid, 500, CheckInterval::Every(5), |done| {
+            calls += 1;
+            if done >= 15 { Err(done) } else { Ok(()) }
+        });
+        assert_eq!(result, Err(15));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn full_proof_getters_describe_the_run() {
+        let result = prove_work_full("alice", "secret", 12, 9, 7).unwrap();
+        let final_grid = run_simulation(generate_grid_from_seed("alice", "secret", 12, 9), 7);
+
+        assert_eq!(result.hash(), hash_grid(&final_grid));
+        assert_eq!((result.width(), result.height(), result.steps()), (12, 9, 7));
+        assert_eq!(result.pixels(), final_grid.as_raw());
+        assert_eq!(result.pixels().len(), 12 * 9);
+    }
 }
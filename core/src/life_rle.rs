@@ -0,0 +1,168 @@
+use crate::grid::{Grid, GridError};
+use std::fmt;
+
+// --- Life RLE Import (the community `.rle` pattern format) ---
+//
+// Not to be confused with the `Storage::Rle` grid encoding. This is the text
+// format Life pattern collections are published in, e.g. a blinker:
+//
+//   #N Blinker
+//   x = 3, y = 1, rule = B3/S23
+//   3o!
+//
+// `#` lines are comments. The optional header gives the pattern's bounding box
+// (and usually a rule, which is ignored here). The body is a sequence of
+// tokens, each with an optional run count: `b` = dead cell, `o` = live cell,
+// `$` = end of row, `!` = end of pattern. Whitespace and line breaks between
+// tokens are insignificant.
+
+/// Why a Life RLE pattern could not be imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifeRleError {
+    /// The `x = .., y = ..` header line is malformed.
+    InvalidHeader(String),
+    /// A character that is not a run count, `b`, `o`, `$` or `!`.
+    UnknownToken(char),
+    /// A run count (or the position it leads to) does not fit in a `usize`.
+    CountOverflow,
+    /// The header's bounding box is larger than the target grid.
+    PatternTooLarge { width: usize, height: usize },
+    /// A live cell lands outside the target grid.
+    OutOfBounds { x: usize, y: usize },
+    /// The input ended before the closing `!`.
+    Unterminated,
+    /// The target grid itself cannot be allocated.
+    Grid(GridError),
+}
+
+impl fmt::Display for LifeRleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifeRleError::InvalidHeader(line) => write!(f, "Invalid RLE header '{}'", line),
+            LifeRleError::UnknownToken(token) => write!(f, "Unknown RLE token '{}'", token),
+            LifeRleError::CountOverflow => write!(f, "RLE run count overflows"),
+            LifeRleError::PatternTooLarge { width, height } => {
+                write!(f, "Pattern is {}x{}, larger than the grid", width, height)
+            }
+            LifeRleError::OutOfBounds { x, y } => write!(f, "Live cell ({}, {}) is outside the grid", x, y),
+            LifeRleError::Unterminated => write!(f, "RLE pattern is missing its closing '!'"),
+            LifeRleError::Grid(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LifeRleError {}
+
+impl From<GridError> for LifeRleError {
+    fn from(err: GridError) -> Self {
+        LifeRleError::Grid(err)
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a pattern in Life's standard RLE format and places it, top-left
+    /// anchored, on an otherwise empty `width x height` grid (live cells = 1).
+    ///
+    /// Errors on unknown tokens, run counts that overflow, a missing `!`, and
+    /// patterns (per the header, or per any live cell) that don't fit the grid.
+    pub fn from_life_rle(text: &str, width: usize, height: usize) -> Result<Grid<u8>, LifeRleError> {
+        let mut grid = Grid::try_new(width, height)?;
+
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let mut body = String::new();
+        if let Some(first) = lines.next() {
+            if first.starts_with('x') {
+                let (pattern_width, pattern_height) = parse_header(first)?;
+                if pattern_width > width || pattern_height > height {
+                    return Err(LifeRleError::PatternTooLarge { width: pattern_width, height: pattern_height });
+                }
+            } else {
+                body.push_str(first);
+            }
+        }
+        body.extend(lines);
+
+        let (mut x, mut y) = (0usize, 0usize);
+        let mut count: Option<usize> = None;
+        for token in body.chars().filter(|c| !c.is_whitespace()) {
+            if let Some(digit) = token.to_digit(10) {
+                let run = count.unwrap_or(0).checked_mul(10).and_then(|run| run.checked_add(digit as usize));
+                count = Some(run.ok_or(LifeRleError::CountOverflow)?);
+                continue;
+            }
+
+            let run = count.take().unwrap_or(1);
+            match token {
+                'b' => x = x.checked_add(run).ok_or(LifeRleError::CountOverflow)?,
+                'o' => {
+                    let end = x.checked_add(run).ok_or(LifeRleError::CountOverflow)?;
+                    if y >= height {
+                        return Err(LifeRleError::OutOfBounds { x, y });
+                    }
+                    if end > width {
+                        return Err(LifeRleError::OutOfBounds { x: x.max(width), y });
+                    }
+                    grid.fill_region(x as isize, y as isize, run, 1, 1);
+                    x = end;
+                }
+                '$' => {
+                    y = y.checked_add(run).ok_or(LifeRleError::CountOverflow)?;
+                    x = 0;
+                }
+                '!' => return Ok(grid),
+                other => return Err(LifeRleError::UnknownToken(other)),
+            }
+        }
+        Err(LifeRleError::Unterminated)
+    }
+}
+
+/// Reads the bounding box from an `x = 3, y = 1[, rule = ...]` header.
+fn parse_header(line: &str) -> Result<(usize, usize), LifeRleError> {
+    let invalid = || LifeRleError::InvalidHeader(line.to_string());
+
+    let mut size = (None, None);
+    for field in line.split(',') {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        let slot = match key.trim() {
+            "x" => &mut size.0,
+            "y" => &mut size.1,
+            _ => continue,
+        };
+        *slot = Some(value.trim().parse::<usize>().map_err(|_| invalid())?);
+    }
+    match size {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::run_simulation;
+
+    #[test]
+    fn blinker_and_glider_parse() {
+        let blinker = Grid::from_life_rle("#N Blinker\nx = 3, y = 1, rule = B3/S23\n3o!\n", 5, 5).unwrap();
+        assert_eq!(blinker, Grid::from_ascii("###..\n.....\n.....\n.....\n.....\n", '#').unwrap());
+        assert_eq!(run_simulation(blinker.clone(), 2), blinker);
+        assert_ne!(run_simulation(blinker.clone(), 1), blinker);
+
+        let glider = Grid::from_life_rle("x = 3, y = 3\nbo$2b\no$3o!", 4, 4).unwrap();
+        assert_eq!(glider, Grid::from_ascii(".#..\n..#.\n###.\n....\n", '#').unwrap());
+        assert_eq!(Grid::from_life_rle("o2$o!", 1, 3).unwrap(), Grid::from_ascii("#\n.\n#\n", '#').unwrap());
+    }
+
+    #[test]
+    fn malformed_patterns_are_rejected() {
+        let parse = |text| Grid::from_life_rle(text, 4, 4);
+        assert_eq!(parse("3o"), Err(LifeRleError::Unterminated));
+        assert_eq!(parse("3q!"), Err(LifeRleError::UnknownToken('q')));
+        assert_eq!(parse("5o!"), Err(LifeRleError::OutOfBounds { x: 4, y: 0 }));
+        assert_eq!(parse("x = 5, y = 1\n5o!"), Err(LifeRleError::PatternTooLarge { width: 5, height: 1 }));
+        assert!(matches!(parse("x = five\no!"), Err(LifeRleError::InvalidHeader(_))));
+        assert_eq!(parse("99999999999999999999999o!"), Err(LifeRleError::CountOverflow));
+    }
+}
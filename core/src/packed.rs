@@ -0,0 +1,162 @@
+use crate::grid::{Grid, GridError};
+use sha2::{Digest, Sha256};
+
+/// A binary (dead/alive) grid storing one bit per cell.
+///
+/// This is 8x smaller than `Grid<u8>`, which is what makes very large grids
+/// practical: a 100000x100000 grid (10^10 cells, more than `u32::MAX`) needs
+/// ~1.25 GB instead of 10 GB. All index math is done in `u64` with checked
+/// multiplication, so nothing truncates around 2^31 or 2^32 cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedGrid {
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+}
+
+/// Largest number of cells a `PackedGrid` may hold.
+///
+/// Coordinates still go through `isize` for wrapping, and the backing `Vec<u64>`
+/// must stay under `isize::MAX` bytes; both hold for anything below this limit.
+pub const MAX_PACKED_CELLS: u64 = isize::MAX as u64;
+
+impl PackedGrid {
+    /// Creates an all-dead packed grid.
+    /// Errors (instead of overflowing) if `width * height` exceeds `MAX_PACKED_CELLS`.
+    pub fn new(width: usize, height: usize) -> Result<Self, GridError> {
+        let cells = (width as u64)
+            .checked_mul(height as u64)
+            .filter(|&cells| cells <= MAX_PACKED_CELLS)
+            .ok_or(GridError::TooLarge { width, height })?;
+
+        Ok(PackedGrid {
+            width,
+            height,
+            words: vec![0; cells.div_ceil(64) as usize],
+        })
+    }
+
+    /// Returns the width of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Total number of cells, as `u64` (it may not fit in `u32`).
+    pub fn cell_count(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Bit index of (x, y) with the same toroidal wrapping as `Grid::get`.
+    #[inline]
+    fn bit_index(&self, x: isize, y: isize) -> u64 {
+        let y_wrapped = y.rem_euclid(self.height as isize) as u64;
+        let x_wrapped = x.rem_euclid(self.width as isize) as u64;
+        y_wrapped * self.width as u64 + x_wrapped
+    }
+
+    /// READS the cell at (x, y) as 0 or 1. Wraps like `Grid::get`.
+    pub fn get(&self, x: isize, y: isize) -> u8 {
+        let bit = self.bit_index(x, y);
+        ((self.words[(bit / 64) as usize] >> (bit % 64)) & 1) as u8
+    }
+
+    /// WRITES the cell at (x, y); any non-zero value stores "alive".
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        let bit = self.bit_index(x as isize, y as isize);
+        let word = &mut self.words[(bit / 64) as usize];
+        let mask = 1u64 << (bit % 64);
+        if value != 0 {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Counts the live cells.
+    pub fn count_alive(&self) -> u64 {
+        // Bits past the last cell are never set, so a plain popcount is exact.
+        self.words.iter().map(|word| u64::from(word.count_ones())).sum()
+    }
+
+    /// SHA-256 of the cells unpacked to one byte (0 or 1) each, row-major: the
+    /// same value as `Grid::logical_hash` on the unpacked grid, computed a word
+    /// at a time without unpacking the whole grid.
+    pub fn logical_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        let mut remaining = self.cell_count();
+        let mut bytes = [0u8; 64];
+        for &word in &self.words {
+            let len = remaining.min(64) as usize;
+            for (bit, byte) in bytes[..len].iter_mut().enumerate() {
+                *byte = ((word >> bit) & 1) as u8;
+            }
+            hasher.update(&bytes[..len]);
+            remaining -= len as u64;
+        }
+        hasher.finalize().into()
+    }
+
+    /// Packs a byte grid (non-zero cells become alive).
+    pub fn from_grid(grid: &Grid<u8>) -> Self {
+        let mut packed = PackedGrid::new(grid.width(), grid.height())
+            .expect("a Grid always fits in a PackedGrid");
+        for (i, &cell) in grid.row_major().as_raw().iter().enumerate() {
+            if cell != 0 {
+                packed.words[i / 64] |= 1 << (i % 64);
+            }
+        }
+        packed
+    }
+
+    /// Unpacks into a byte grid of 0/1 cells.
+    /// Panics if the grid is too large for `Grid<u8>` (see `grid::MAX_CELLS`).
+    pub fn to_grid(&self) -> Grid<u8> {
+        let mut grid = Grid::new(self.width, self.height);
+        for (i, cell) in grid.as_raw_mut().iter_mut().enumerate() {
+            *cell = ((self.words[i / 64] >> (i % 64)) & 1) as u8;
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn indexes_past_u32_cells() {
+        // About 600 MB of zeroed words; only the touched pages are ever mapped.
+        let mut grid = PackedGrid::new(70_000, 70_000).unwrap();
+        assert_eq!(grid.cell_count(), 4_900_000_000);
+        assert!(grid.cell_count() > u64::from(u32::MAX));
+
+        grid.set(69_999, 69_999, 1);
+        grid.set(1, 61_400, 1); // bit 61_400 * 70_000 + 1 > u32::MAX
+        assert_eq!(grid.get(-1, -1), 1);
+        assert_eq!(grid.get(1, 61_400), 1);
+        assert_eq!(grid.get(0, 61_400), 0);
+        assert_eq!(grid.count_alive(), 2);
+    }
+
+    #[test]
+    fn too_many_cells_is_an_error() {
+        assert!(matches!(PackedGrid::new(usize::MAX, 2), Err(GridError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn logical_hashes_agree_with_the_byte_grid() {
+        // 13 x 11 = 143 cells: two full words and a partial one.
+        let grid = crate::engine::generate_grid_from_seed("alice", "secret", 13, 11);
+        let packed = PackedGrid::from_grid(&grid);
+        assert_eq!(packed.logical_hash(), grid.logical_hash());
+        assert_eq!(hex::encode(packed.logical_hash()), crate::engine::hash_grid(&grid));
+        assert_eq!(packed.logical_hash(), grid.clone().into_layout(crate::grid::Layout::ColMajor).logical_hash());
+        assert_ne!(packed.logical_hash(), PackedGrid::from_grid(&grid.shift(1, 0)).logical_hash());
+    }
+}
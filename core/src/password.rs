@@ -0,0 +1,54 @@
+// --- Credential Strength ---
+//
+// The initial grid is fully determined by the credentials, so a weak password
+// means a guessable grid (and a worthless proof). This is a deliberately simple
+// charset-size heuristic, not a full strength meter (no dictionary checks).
+
+/// Size of the pool assumed for characters outside printable ASCII.
+const OTHER_CHARSET: u32 = 100;
+
+/// Estimates the entropy of a password in bits.
+///
+/// Each character class present (lowercase, uppercase, digits, ASCII symbols,
+/// anything else) adds its size to the assumed charset, and the estimate is
+/// `length * log2(charset)`. An empty password scores 0.
+pub fn estimate_password_bits(password: &str) -> f64 {
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) = (false, false, false, false, false);
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii_graphic() || c == ' ' => symbol = true,
+            _ => other = true,
+        }
+    }
+
+    let charset: u32 = [(lower, 26), (upper, 26), (digit, 10), (symbol, 33), (other, OTHER_CHARSET)]
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, size)| size)
+        .sum();
+
+    if charset == 0 {
+        return 0.0;
+    }
+    password.chars().count() as f64 * f64::from(charset).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_passwords_score_low_and_strong_ones_high() {
+        assert_eq!(estimate_password_bits(""), 0.0);
+        assert!(estimate_password_bits("1234") < 20.0);
+        assert!(estimate_password_bits("password") < 40.0);
+
+        let strong = estimate_password_bits("Tr0ub4dor&3-horse-Battery!");
+        assert!(strong > 120.0, "{}", strong);
+        // 8 lowercase letters: exactly 8 * log2(26).
+        assert!((estimate_password_bits("abcdefgh") - 8.0 * 26f64.log2()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::grid::Grid;
+use std::fmt::Write;
+
+// --- PPM Encoding ---
+//
+// Plain-text Netpbm (P3): every viewer reads it and it needs no libraries.
+// Encoding is kept separate from I/O so the bytes can go to a file (CLI),
+// stay in memory (tests, embedding) or be handed to JavaScript (WASM).
+
+/// An RGB color.
+pub type Rgb = (u8, u8, u8);
+
+/// Black ink on a white page, the CLI's default look.
+pub const INK_BLACK: Rgb = (0, 0, 0);
+pub const PAPER_WHITE: Rgb = (255, 255, 255);
+
+/// How `encode_ppm_as` decides what a cell looks like.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AliveTest {
+    /// Any non-zero cell is `INK_BLACK`, zero is `PAPER_WHITE`. Binary Life
+    /// renders as usual and multi-state cells (e.g. decaying values) as ink.
+    NonZero,
+    /// Value maps to darkness: 0 = white, 255 = black, in-between = grey.
+    Brightness,
+}
+
+impl AliveTest {
+    /// The color of one cell under this test.
+    pub fn color(self, cell: u8) -> Rgb {
+        match self {
+            AliveTest::NonZero if cell != 0 => INK_BLACK,
+            AliveTest::NonZero => PAPER_WHITE,
+            AliveTest::Brightness => {
+                let level = 255 - cell;
+                (level, level, level)
+            }
+        }
+    }
+}
+
+/// Encodes the grid as a P3 image: non-zero cells are `ink`, empty cells `bg`.
+pub fn encode_ppm(grid: &Grid<u8>, ink: Rgb, bg: Rgb) -> Vec<u8> {
+    encode_ppm_with(grid, |cell| if cell != 0 { ink } else { bg })
+}
+
+/// Encodes the grid as a P3 image, coloring each cell with `alive_test`.
+pub fn encode_ppm_as(grid: &Grid<u8>, alive_test: AliveTest) -> Vec<u8> {
+    encode_ppm_with(grid, |cell| alive_test.color(cell))
+}
+
+/// Encodes the grid as a P3 image, asking `color` for each cell's RGB value.
+/// Works for any cell type, e.g. `Grid<u32>` component labels with `palette_color`.
+///
+/// Layout: a `P3\n{width} {height}\n255\n` header, then one text line per row
+/// of `r g b ` triplets.
+pub fn encode_ppm_with<T: Copy + Default>(grid: &Grid<T>, color: impl Fn(T) -> Rgb) -> Vec<u8> {
+    let mut out = format!("P3\n{} {}\n255\n", grid.width(), grid.height());
+    let width = grid.width();
+    let grid = grid.row_major();
+    for y in 0..grid.height() {
+        for &cell in &grid.as_raw()[y * width..(y + 1) * width] {
+            let (r, g, b) = color(cell);
+            // Writing to a String cannot fail.
+            let _ = write!(out, "{} {} {} ", r, g, b);
+        }
+        out.push('\n');
+    }
+    out.into_bytes()
+}
+
+// --- Label Palette ---
+
+/// Background for label 0 in `palette_color` (and `encode_ppm_labels`).
+pub const LABEL_BACKGROUND: Rgb = PAPER_WHITE;
+
+/// Golden-ratio conjugate: successive labels land far apart on the hue wheel.
+const GOLDEN_STEP: f64 = 0.618_033_988_749_895;
+
+/// A stable, visually distinct color for a label or state value.
+///
+/// 0 is `LABEL_BACKGROUND`. Other values step around the hue wheel by the golden
+/// ratio, so neighboring labels get very different hues, and saturation and
+/// brightness vary on their own irrational steps, so labels whose hues come out
+/// close still differ. Never white, so no label blends into the background.
+/// The same value always gets the same color, on every platform and run.
+pub fn palette_color(value: u32) -> Rgb {
+    if value == 0 {
+        return LABEL_BACKGROUND;
+    }
+    let step = |factor: f64| (f64::from(value) * factor).fract();
+    let hue = step(GOLDEN_STEP);
+    let saturation = 0.55 + 0.4 * step(0.754_877_666_246_693);
+    let brightness = 0.6 + 0.35 * step(0.569_840_290_998_053);
+    hsv_to_rgb(hue, saturation, brightness)
+}
+
+/// Converts HSV (each in `0.0..1.0`) to 8-bit RGB.
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> Rgb {
+    let sector = hue * 6.0;
+    let chroma = value * saturation;
+    let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let base = value - chroma;
+    let channel = |c: f64| ((c + base) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Encodes a label grid (e.g. from `Grid::connected_components`) as a P3 image,
+/// one `palette_color` per label, with label 0 as the background.
+pub fn encode_ppm_labels(labels: &Grid<u32>) -> Vec<u8> {
+    encode_ppm_with(labels, palette_color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::grid::Layout;
+
+    /// Splits a P3 image into (width, height, rgb triplets).
+    fn parse_p3(bytes: &[u8]) -> (usize, usize, Vec<Rgb>) {
+        let text = std::str::from_utf8(bytes).unwrap();
+        let mut tokens = text.split_whitespace();
+        assert_eq!(tokens.next(), Some("P3"));
+        let mut number = || tokens.next().unwrap().parse::<usize>().unwrap();
+        let (width, height, max) = (number(), number(), number());
+        assert_eq!(max, 255);
+        let values: Vec<u8> = tokens.map(|token| token.parse().unwrap()).collect();
+        assert_eq!(values.len(), width * height * 3);
+        (width, height, values.chunks(3).map(|rgb| (rgb[0], rgb[1], rgb[2])).collect())
+    }
+
+    #[test]
+    fn encoded_images_parse_back_cell_for_cell() {
+        let grid = Grid::from_ascii("#..\n.#.\n..#\n##.\n", '#').unwrap();
+        let (red, blue) = ((255, 0, 0), (0, 0, 255));
+        let bytes = encode_ppm(&grid, red, blue);
+        assert!(bytes.starts_with(b"P3\n3 4\n255\n"));
+        assert_eq!(std::str::from_utf8(&bytes).unwrap().lines().count(), 3 + 4);
+
+        let (width, height, pixels) = parse_p3(&bytes);
+        assert_eq!((width, height), (3, 4));
+        let expected: Vec<Rgb> = grid.as_raw().iter().map(|&cell| if cell != 0 { red } else { blue }).collect();
+        assert_eq!(pixels, expected);
+        assert_eq!(encode_ppm(&grid.into_layout(Layout::ColMajor), red, blue), bytes);
+    }
+
+    #[test]
+    fn alive_tests_color_multi_state_cells() {
+        let colors = |test: AliveTest| [0, 1, 128, 255].map(|cell| test.color(cell));
+        let (white, black) = (PAPER_WHITE, INK_BLACK);
+        assert_eq!(colors(AliveTest::NonZero), [white, black, black, black]);
+        assert_eq!(colors(AliveTest::Brightness), [white, (254, 254, 254), (127, 127, 127), black]);
+
+        let grid = Grid::from_ascii("#.\n.#\n", '#').unwrap();
+        assert_eq!(encode_ppm_as(&grid, AliveTest::NonZero), encode_ppm(&grid, INK_BLACK, PAPER_WHITE));
+    }
+
+    #[test]
+    fn palette_colors_are_distinct_and_never_the_background() {
+        assert_eq!(palette_color(0), LABEL_BACKGROUND);
+        let colors: Vec<Rgb> = (1..=256).map(palette_color).collect();
+        assert!(colors.iter().all(|&color| color != LABEL_BACKGROUND));
+
+        let mut unique = colors.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), colors.len());
+        // Pinned, so a change to the palette math shows up here.
+        assert_eq!(palette_color(1), (30, 81, 204));
+    }
+}
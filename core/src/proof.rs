@@ -0,0 +1,938 @@
+use crate::engine::{generate_grid_warmed, run_simulation, try_generate_grid_warmed, HashAlgo};
+use crate::grid::{cell_count, Grid, GridError};
+use crate::storage::Storage;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+// --- Self-Describing Proof Strings ---
+//
+// Format: `v2:{width}x{height}:{steps}:{hash}`
+// e.g.    `v2:500x500:1000:3f9a...` (hash = 64 lowercase hex chars)
+//
+// Proofs using another `HashAlgo` name it before the hash:
+//         `v2:500x500:1000:sha512:9b1c...` (hash = 2 hex chars per digest byte)
+// and proofs with a stretched seed give the iteration count after the steps:
+//         `v2:500x500:1000:i50000:3f9a...` or `v2:500x500:1000:i50000:sha512:9b1c...`
+// followed by the PRNG warm-up, if any:
+//         `v2:500x500:1000:w16:3f9a...` or `v2:500x500:1000:i50000:w16:3f9a...`
+// so plain SHA-256, single-hash proofs keep exactly the original format.
+//
+// A bare hash tells the verifier nothing about how it was computed, so a
+// parameter mismatch just reads as "invalid". Embedding the parameters lets
+// the verifier re-derive with the right ones (or report a clear mismatch).
+//
+// `v2` is `v1` with the credentials length-prefixed in the master hash (see
+// `engine::credential_hasher`), which changed every seed. A `v1` string is
+// refused as an unsupported version instead of just failing to match.
+
+/// Version tag of the current proof string format.
+const PROOF_VERSION: &str = "v2";
+
+/// The parameters a proof was computed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProofParams {
+    pub width: usize,
+    pub height: usize,
+    pub steps: usize,
+    /// Digest over the final grid (`HashAlgo::Sha256` unless chosen otherwise).
+    pub algo: HashAlgo,
+    /// SHA-256 rounds in the seed derivation (see `derive_seed`); 1 = a single hash.
+    pub iterations: u32,
+    /// PRNG outputs discarded before seeding (see `generate_grid_warmed`); 0 = none.
+    pub warmup: u32,
+}
+
+impl ProofParams {
+    /// The initial grid these parameters seed from the credentials.
+    pub fn seed_grid(&self, username: &str, password: &str) -> Grid<u8> {
+        generate_grid_warmed(username, password, self.width, self.height, self.iterations, self.warmup)
+    }
+
+    /// Fallible version of `seed_grid`, for sizes that came from user input.
+    pub fn try_seed_grid(&self, username: &str, password: &str) -> Result<Grid<u8>, GridError> {
+        try_generate_grid_warmed(username, password, self.width, self.height, self.iterations, self.warmup)
+    }
+
+    /// Runs the standard proof (Seed -> Simulation -> Hash) with these parameters.
+    pub fn prove(&self, username: &str, password: &str) -> String {
+        self.prove_with_context(username, password, &[])
+    }
+
+    /// Same as `prove`, with a challenge `context` mixed into the final hash
+    /// (see `hash_grid_with_context`).
+    pub fn prove_with_context(&self, username: &str, password: &str, context: &[u8]) -> String {
+        let final_grid = run_simulation(self.seed_grid(username, password), self.steps);
+        self.algo.hash_grid(&final_grid, context)
+    }
+}
+
+/// Why a proof string could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// The version tag is not one we understand.
+    UnsupportedVersion(String),
+    /// The string does not follow `v2:{w}x{h}:{steps}:{hash}`.
+    Malformed(String),
+    /// A binary artifact's CRC does not match its contents.
+    ChecksumMismatch,
+    /// Replaying from the seed needs an artifact that embeds one.
+    MissingSeed,
+    /// The proof's grid or run is bigger than the verifier will re-derive
+    /// (see `VerifyLimits`), or too big to build at all.
+    OverLimit(String),
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::UnsupportedVersion(version) => write!(f, "Unsupported proof version '{}'", version),
+            ProofError::Malformed(reason) => write!(f, "Malformed proof: {}", reason),
+            ProofError::ChecksumMismatch => write!(f, "Proof artifact is corrupt (CRC mismatch)"),
+            ProofError::MissingSeed => write!(f, "Proof artifact has no embedded seed"),
+            ProofError::OverLimit(reason) => write!(f, "Proof is over the verifier's limits: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+/// Formats a proof as `v2:{width}x{height}:{steps}:{hash}`, with the iteration
+/// count after the steps unless it is 1, then the warm-up unless it is 0, and
+/// the algorithm before the hash unless it is plain SHA-256.
+pub fn format_proof(params: &ProofParams, hash: &str) -> String {
+    let mut proof = format!("{}:{}x{}:{}", PROOF_VERSION, params.width, params.height, params.steps);
+    if params.iterations != 1 {
+        proof.push_str(&format!(":i{}", params.iterations));
+    }
+    if params.warmup != 0 {
+        proof.push_str(&format!(":w{}", params.warmup));
+    }
+    if params.algo != HashAlgo::Sha256 {
+        proof.push_str(&format!(":{}", params.algo));
+    }
+    format!("{}:{}", proof, hash)
+}
+
+/// Parses a proof string produced by `format_proof`.
+pub fn parse_proof(text: &str) -> Result<(ProofParams, String), ProofError> {
+    let malformed = |reason: &str| ProofError::Malformed(reason.to_string());
+
+    let mut fields = text.trim().split(':');
+    let version = fields.next().unwrap_or_default();
+    if version != PROOF_VERSION {
+        return Err(ProofError::UnsupportedVersion(version.to_string()));
+    }
+
+    let fields: Vec<&str> = fields.collect();
+    let (size, steps, options, hash) = match fields[..] {
+        [size, steps, ref options @ .., hash] if options.len() <= 3 => (size, steps, options, hash),
+        _ => return Err(malformed("expected 4 to 7 ':'-separated fields")),
+    };
+
+    // Optional fields, in order: `i{iterations}`, `w{warmup}`, then the algorithm name.
+    let mut options = options.iter().peekable();
+    let iterations = match options.next_if(|field| field.starts_with('i')) {
+        Some(field) => field[1..]
+            .parse()
+            .ok()
+            .filter(|&iterations| iterations > 0)
+            .ok_or_else(|| malformed("invalid iteration count"))?,
+        None => 1,
+    };
+    let warmup = match options.next_if(|field| field.starts_with('w')) {
+        Some(field) => field[1..].parse().map_err(|_| malformed("invalid warm-up count"))?,
+        None => 0,
+    };
+    let algo = match options.next() {
+        Some(name) => name.parse().map_err(|err: String| malformed(&err))?,
+        None => HashAlgo::Sha256,
+    };
+    if options.next().is_some() {
+        return Err(malformed("unexpected field before the hash"));
+    }
+
+    let (width, height) = size.split_once('x').ok_or_else(|| malformed("size must be WxH"))?;
+    let width = width.parse().map_err(|_| malformed("invalid width"))?;
+    let height = height.parse().map_err(|_| malformed("invalid height"))?;
+    let steps = steps.parse().map_err(|_| malformed("invalid step count"))?;
+
+    let hex_len = algo.output_len() * 2;
+    let is_hex = hash.len() == hex_len && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    if !is_hex {
+        return Err(malformed(&format!("{} hash must be {} lowercase hex characters", algo, hex_len)));
+    }
+
+    Ok((ProofParams { width, height, steps, algo, iterations, warmup }, hash.to_string()))
+}
+
+/// Re-derives the proof from the credentials using the parameters embedded in
+/// `proof` and checks that the hash matches.
+pub fn verify_proof(username: &str, password: &str, proof: &str) -> Result<bool, ProofError> {
+    verify_proof_with_context(username, password, proof, &[])
+}
+
+/// Same as `verify_proof` for a proof bound to `context`; the verifier must
+/// supply the same context the prover used.
+pub fn verify_proof_with_context(
+    username: &str,
+    password: &str,
+    proof: &str,
+    context: &[u8],
+) -> Result<bool, ProofError> {
+    verify_artifact_with_context(username, password, &ProofArtifact::from_proof(proof)?, context)
+}
+
+/// `verify_proof` for an already-decoded `ProofArtifact`: re-derives with the
+/// artifact's own size, steps, algorithm, iterations and warm-up and compares digests,
+/// so there are no loose parameters to get out of sync. An embedded seed is
+/// ignored; the credentials are what is being checked.
+///
+/// A grid too big to build is `ProofError::OverLimit`, but any buildable size
+/// and step count is re-run in full: for proofs from clients, use
+/// `verify_artifact_with_limits`.
+pub fn verify_artifact(username: &str, password: &str, artifact: &ProofArtifact) -> Result<bool, ProofError> {
+    verify_artifact_with_context(username, password, artifact, &[])
+}
+
+/// Same as `verify_artifact` for a proof bound to `context`. Artifacts do not
+/// store the context: it is the verifier's own challenge, so it comes from
+/// the verifier, not from what the client sent.
+pub fn verify_artifact_with_context(
+    username: &str,
+    password: &str,
+    artifact: &ProofArtifact,
+    context: &[u8],
+) -> Result<bool, ProofError> {
+    let params = &artifact.params;
+    let seed = params
+        .try_seed_grid(username, password)
+        .map_err(|err| ProofError::OverLimit(err.to_string()))?;
+    let final_grid = run_simulation(seed, params.steps);
+    Ok(constant_time_eq(&params.algo.digest(&final_grid, context), &artifact.digest))
+}
+
+/// `verify_artifact_with_context` that first holds the artifact to `limits`,
+/// as `verify_stream` does: a proof over any of them is `ProofError::OverLimit`
+/// before a single cell is allocated.
+pub fn verify_artifact_with_limits(
+    username: &str,
+    password: &str,
+    artifact: &ProofArtifact,
+    context: &[u8],
+    limits: &VerifyLimits,
+) -> Result<bool, ProofError> {
+    limits.check(&artifact.params).map_err(ProofError::OverLimit)?;
+    verify_artifact_with_context(username, password, artifact, context)
+}
+
+// --- Streaming Verification (Line-Delimited Filter) ---
+//
+// Input, one submission per line:   <username> <proof>
+// where <proof> is a `v2:...` string or a `--short` base32 token. Blank lines
+// are skipped. Output, one line per submission, in input order:
+//   VALID <username>
+//   INVALID <username>
+//   ERROR <line number>: <reason>      (unparseable line, proof, unknown user,
+//                                       or parameters over the `VerifyLimits`)
+//
+// A bad line never stops the stream; only I/O errors on the reader or writer do.
+// Every size in a submission comes from the sender, so each one is checked
+// against the limits before any grid is built.
+
+/// The largest proof `verify_stream` will re-run. Submissions over any limit
+/// get an `ERROR` line instead of a grid that exhausts memory or a run that
+/// pins the verifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VerifyLimits {
+    /// Most cells (`width * height`) in the grid.
+    pub max_cells: usize,
+    /// Most simulation steps.
+    pub max_steps: usize,
+    /// Most SHA-256 rounds in the seed derivation (see `derive_seed`).
+    pub max_iterations: u32,
+}
+
+impl Default for VerifyLimits {
+    /// 4096 x 4096 cells (the largest grid `recommended_params` picks),
+    /// 100 000 steps and 1 000 000 seed iterations.
+    fn default() -> Self {
+        VerifyLimits {
+            max_cells: 4096 * 4096,
+            max_steps: 100_000,
+            max_iterations: 1_000_000,
+        }
+    }
+}
+
+impl VerifyLimits {
+    /// Why `params` are over these limits, or `Ok` if they are within them.
+    pub fn check(&self, params: &ProofParams) -> Result<(), String> {
+        let cells = cell_count(params.width, params.height).map_err(|err| err.to_string())?;
+        if cells > self.max_cells {
+            return Err(format!("grid of {} cells exceeds the limit of {}", cells, self.max_cells));
+        }
+        if params.steps > self.max_steps {
+            return Err(format!("{} steps exceeds the limit of {}", params.steps, self.max_steps));
+        }
+        if params.iterations > self.max_iterations {
+            return Err(format!(
+                "{} seed iterations exceeds the limit of {}",
+                params.iterations, self.max_iterations
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Verifies every submission read from `reader` and writes one result line per
+/// submission to `writer` (see the format above), looking passwords up with
+/// `credentials`. Results are flushed after each line, so it works as a pipe.
+/// Submissions are held to `VerifyLimits::default()`.
+pub fn verify_stream<R, W, F>(reader: R, writer: W, credentials: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> Option<String>,
+{
+    verify_stream_with_limits(reader, writer, VerifyLimits::default(), credentials)
+}
+
+/// Same as `verify_stream`, holding every submission to `limits`.
+pub fn verify_stream_with_limits<R, W, F>(
+    mut reader: R,
+    mut writer: W,
+    limits: VerifyLimits,
+    mut credentials: F,
+) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut line = Vec::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        number += 1;
+
+        let text = match std::str::from_utf8(&line) {
+            Ok(text) if text.trim().is_empty() => continue,
+            Ok(text) => text,
+            Err(_) => {
+                writeln!(writer, "ERROR {}: line is not valid UTF-8", number)?;
+                continue;
+            }
+        };
+        match verify_submission(text, &limits, &mut credentials) {
+            Ok((username, true)) => writeln!(writer, "VALID {}", username)?,
+            Ok((username, false)) => writeln!(writer, "INVALID {}", username)?,
+            Err(reason) => writeln!(writer, "ERROR {}: {}", number, reason)?,
+        }
+        writer.flush()?;
+    }
+}
+
+/// Checks one `<username> <proof>` line, returning the username and the verdict.
+fn verify_submission<'a>(
+    line: &'a str,
+    limits: &VerifyLimits,
+    credentials: &mut impl FnMut(&str) -> Option<String>,
+) -> Result<(&'a str, bool), String> {
+    let mut fields = line.split_whitespace();
+    let (Some(username), Some(proof), None) = (fields.next(), fields.next(), fields.next()) else {
+        return Err("expected '<username> <proof>'".to_string());
+    };
+
+    // Proof strings always contain ':'; base32 tokens never do.
+    let artifact = if proof.contains(':') {
+        ProofArtifact::from_proof(proof)
+    } else {
+        ProofArtifact::from_short(proof)
+    }
+    .map_err(|err| err.to_string())?;
+    limits.check(&artifact.params)?;
+
+    let password = credentials(username).ok_or_else(|| format!("unknown user '{}'", username))?;
+    let valid = verify_artifact(username, &password, &artifact).map_err(|err| err.to_string())?;
+    Ok((username, valid))
+}
+
+/// `verify_proof` for servers checking proofs over huge grids.
+///
+/// Re-derives the seed and hashes the run with `HashAlgo::digest_streaming`:
+/// the last tick goes into the hasher row by row, so memory peaks at two grid
+/// buffers whatever the step count. The digest is compared with the claimed
+/// one in constant time, so response timing does not reveal how many leading
+/// bytes of a guessed hash were right. A seed too large to allocate is
+/// `ProofError::OverLimit`, not a panic.
+pub fn verify_streaming(username: &str, password: &str, proof: &str) -> Result<bool, ProofError> {
+    verify_streaming_with_context(username, password, proof, &[])
+}
+
+/// `verify_streaming` for a proof bound to a challenge `context`
+/// (see `verify_proof_with_context`).
+pub fn verify_streaming_with_context(
+    username: &str,
+    password: &str,
+    proof: &str,
+    context: &[u8],
+) -> Result<bool, ProofError> {
+    let artifact = ProofArtifact::from_proof(proof)?;
+    let params = &artifact.params;
+    let seed = params
+        .try_seed_grid(username, password)
+        .map_err(|err| ProofError::OverLimit(err.to_string()))?;
+    let digest = params.algo.digest_streaming(seed, params.steps, context);
+    Ok(constant_time_eq(&digest, &artifact.digest))
+}
+
+/// Byte-slice equality whose running time depends only on the lengths,
+/// never on where the first difference is.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// --- Binary Proof Artifacts (.ivp) ---
+//
+// Layout (all integers unsigned):
+//   version: u8 (5, 6 for a stretched seed, 7 for an embedded seed, or 8 for
+//            a PRNG warm-up)
+//   width, height, steps: LEB128 varints
+//   iterations: LEB128 varint (versions 6-8; version 5 means 1)
+//   warmup: LEB128 varint (version 8 only; earlier versions mean 0)
+//   algorithm: u8 (0 = SHA-256, 1 = SHA-512, 2 = truncated SHA-256 followed by
+//              a length byte)
+//   digest: `HashAlgo::output_len` bytes
+//   seed: the initial grid in the `INKG` format (always in version 7, optional
+//         in version 8, absent otherwise; see storage.rs)
+//   crc: u32 little-endian, CRC-32 (IEEE) of everything before it
+//
+// Versions 1-4 were the same four layouts before the `v2` seed change (see
+// `PROOF_VERSION`). Their seeds can no longer be derived, so they are refused
+// as unsupported instead of decoding fine and then never verifying.
+//
+// A typical 500x500x1000 proof is 44 bytes instead of ~80 characters of text.
+//
+// Audit artifacts (version 7) also carry the seed grid, so an auditor can
+// replay the simulation and check the digest without ever seeing the password.
+// The seed is stored packed or run-length encoded, whichever is smaller: ~1/8
+// of a byte per cell for a random seed. Note that anyone holding the seed can
+// test password guesses against it without running the simulation, so audit
+// artifacts should be handled like password hashes.
+//
+// Short form: the same bytes in unpadded base32 (RFC 4648 alphabet, A-Z and
+// 2-7), a single 71-character token for a typical proof. It has no
+// lowercase/uppercase or 0/O, 1/I/l ambiguity, so it survives being read out
+// and fits QR codes' compact alphanumeric mode.
+
+/// Version byte of the plain artifact layout (single-hash seed).
+const ARTIFACT_VERSION: u8 = 5;
+
+/// Version byte of the layout with a seed iteration count. Only written when
+/// `iterations != 1`, so single-hash artifacts keep the shorter plain layout.
+const ARTIFACT_VERSION_ITERATIONS: u8 = 6;
+
+/// Version byte of the layout with an embedded seed grid.
+const ARTIFACT_VERSION_SEED: u8 = 7;
+
+/// Version byte of the layout with a PRNG warm-up; the seed grid is optional
+/// (present if bytes follow the digest). Only written when `warmup != 0`.
+const ARTIFACT_VERSION_WARMUP: u8 = 8;
+
+/// Digits of the short form, value `i` at index `i`.
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Algorithm ids (see `HashAlgo`).
+const ALGORITHM_SHA256: u8 = 0;
+const ALGORITHM_SHA512: u8 = 1;
+const ALGORITHM_SHA256_TRUNC: u8 = 2;
+
+/// A proof packed into a compact, checksummed binary blob.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProofArtifact {
+    pub params: ProofParams,
+    /// Raw digest bytes (`params.algo.output_len()` of them).
+    pub digest: Vec<u8>,
+    /// The initial grid, for replaying without the credentials (see `with_seed`).
+    pub seed: Option<Grid<u8>>,
+}
+
+impl ProofArtifact {
+    /// Builds an artifact from a proof string (see `parse_proof`).
+    pub fn from_proof(text: &str) -> Result<Self, ProofError> {
+        let (params, hash) = parse_proof(text)?;
+        let digest = hex::decode(&hash).map_err(|_| ProofError::Malformed("invalid hash".to_string()))?;
+        Ok(ProofArtifact { params, digest, seed: None })
+    }
+
+    /// The equivalent `v2:...` proof string. Proof strings have no room for a
+    /// seed, so an embedded one is dropped.
+    pub fn to_proof(&self) -> String {
+        format_proof(&self.params, &hex::encode(&self.digest))
+    }
+
+    /// Packs the artifact (see the layout above).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let version = match (&self.seed, self.params.iterations, self.params.warmup) {
+            (_, _, 1..) => ARTIFACT_VERSION_WARMUP,
+            (Some(_), _, 0) => ARTIFACT_VERSION_SEED,
+            (None, 1, 0) => ARTIFACT_VERSION,
+            (None, _, 0) => ARTIFACT_VERSION_ITERATIONS,
+        };
+        let mut bytes = vec![version];
+        for value in [self.params.width, self.params.height, self.params.steps] {
+            write_varint(&mut bytes, value as u64);
+        }
+        if version != ARTIFACT_VERSION {
+            write_varint(&mut bytes, u64::from(self.params.iterations));
+        }
+        if version == ARTIFACT_VERSION_WARMUP {
+            write_varint(&mut bytes, u64::from(self.params.warmup));
+        }
+        match self.params.algo {
+            HashAlgo::Sha256 => bytes.push(ALGORITHM_SHA256),
+            HashAlgo::Sha512 => bytes.push(ALGORITHM_SHA512),
+            // Valid truncations are at most 32 bytes, so the length fits a byte.
+            HashAlgo::Sha256Trunc(len) => bytes.extend_from_slice(&[ALGORITHM_SHA256_TRUNC, len as u8]),
+        }
+        bytes.extend_from_slice(&self.digest);
+        if let Some(seed) = &self.seed {
+            bytes.extend_from_slice(&encode_seed(seed));
+        }
+        let crc = crc32(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Unpacks an artifact produced by `to_bytes`, checking the CRC first.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        let malformed = |reason: &str| ProofError::Malformed(reason.to_string());
+
+        let (body, crc) = bytes
+            .split_last_chunk::<4>()
+            .ok_or_else(|| malformed("artifact too short"))?;
+        if crc32(body) != u32::from_le_bytes(*crc) {
+            return Err(ProofError::ChecksumMismatch);
+        }
+
+        let (&version, mut rest) = body.split_first().ok_or_else(|| malformed("artifact too short"))?;
+        let known = [ARTIFACT_VERSION, ARTIFACT_VERSION_ITERATIONS, ARTIFACT_VERSION_SEED, ARTIFACT_VERSION_WARMUP];
+        if !known.contains(&version) {
+            return Err(ProofError::UnsupportedVersion(version.to_string()));
+        }
+
+        let mut field = || {
+            let value = read_varint(&mut rest).ok_or_else(|| malformed("invalid varint"))?;
+            usize::try_from(value).map_err(|_| malformed("dimension too large"))
+        };
+        let (width, height, steps) = (field()?, field()?, field()?);
+        let iterations = if version != ARTIFACT_VERSION {
+            read_varint(&mut rest)
+                .and_then(|value| u32::try_from(value).ok())
+                .filter(|&iterations| iterations > 0)
+                .ok_or_else(|| malformed("invalid iteration count"))?
+        } else {
+            1
+        };
+        let warmup = if version == ARTIFACT_VERSION_WARMUP {
+            read_varint(&mut rest)
+                .and_then(|value| u32::try_from(value).ok())
+                .ok_or_else(|| malformed("invalid warm-up count"))?
+        } else {
+            0
+        };
+
+        let (&algorithm, mut rest) = rest.split_first().ok_or_else(|| malformed("missing algorithm"))?;
+        let algo = match algorithm {
+            ALGORITHM_SHA256 => HashAlgo::Sha256,
+            ALGORITHM_SHA512 => HashAlgo::Sha512,
+            ALGORITHM_SHA256_TRUNC => {
+                let (&len, digest) = rest.split_first().ok_or_else(|| malformed("missing truncation length"))?;
+                rest = digest;
+                HashAlgo::Sha256Trunc(usize::from(len))
+            }
+            other => return Err(malformed(&format!("unknown hash algorithm id {}", other))),
+        };
+        if !algo.is_valid() {
+            return Err(malformed(&format!("invalid hash algorithm {}", algo)));
+        }
+        let has_seed = match version {
+            ARTIFACT_VERSION_SEED => true,
+            ARTIFACT_VERSION_WARMUP => rest.len() > algo.output_len(),
+            _ => false,
+        };
+        if rest.len() < algo.output_len() || (!has_seed && rest.len() != algo.output_len()) {
+            return Err(malformed(&format!("{} digest must be {} bytes", algo, algo.output_len())));
+        }
+        let (digest, mut rest) = rest.split_at(algo.output_len());
+
+        let seed = if has_seed {
+            let seed = Grid::read_from(&mut rest).map_err(|err| malformed(&format!("invalid seed: {}", err)))?;
+            if (seed.width(), seed.height()) != (width, height) {
+                return Err(malformed("seed size does not match the proof"));
+            }
+            if !rest.is_empty() {
+                return Err(malformed("unexpected bytes after the seed"));
+            }
+            Some(seed)
+        } else {
+            None
+        };
+
+        Ok(ProofArtifact {
+            params: ProofParams { width, height, steps, algo, iterations, warmup },
+            digest: digest.to_vec(),
+            seed,
+        })
+    }
+}
+
+// --- Audit Replay (Embedded Seed) ---
+
+impl ProofArtifact {
+    /// Embeds `seed`, the initial grid the proof was computed from, so the
+    /// artifact can be checked with `verify_seed` instead of the credentials.
+    pub fn with_seed(self, seed: Grid<u8>) -> Self {
+        ProofArtifact { seed: Some(seed), ..self }
+    }
+
+    /// Replays the simulation from the embedded seed and checks the digest,
+    /// with no credentials involved. This shows the digest follows from the
+    /// seed; `verify_proof` is still what ties a proof to a password.
+    ///
+    /// Errors with `ProofError::MissingSeed` if the artifact has no seed.
+    pub fn verify_seed(&self) -> Result<bool, ProofError> {
+        self.verify_seed_with_context(&[])
+    }
+
+    /// Same as `verify_seed` for a proof bound to `context`.
+    pub fn verify_seed_with_context(&self, context: &[u8]) -> Result<bool, ProofError> {
+        let seed = self.seed.as_ref().ok_or(ProofError::MissingSeed)?;
+        if (seed.width(), seed.height()) != (self.params.width, self.params.height) {
+            return Ok(false);
+        }
+        let final_grid = run_simulation(seed.clone(), self.params.steps);
+        Ok(constant_time_eq(&self.params.algo.digest(&final_grid, context), &self.digest))
+    }
+}
+
+/// The seed in `INKG` format, packed or run-length encoded, whichever is smaller.
+fn encode_seed(seed: &Grid<u8>) -> Vec<u8> {
+    [Storage::Packed, Storage::Rle]
+        .into_iter()
+        .filter_map(|storage| {
+            let mut bytes = Vec::new();
+            seed.write_to(&mut bytes, storage).ok().map(|()| bytes)
+        })
+        .min_by_key(Vec::len)
+        .expect("RLE storage accepts any grid")
+}
+
+impl ProofArtifact {
+    /// The short form: `to_bytes` as one base32 token (see the layout above).
+    pub fn to_short(&self) -> String {
+        let bytes = self.to_bytes();
+        let mut token = String::with_capacity((bytes.len() * 8).div_ceil(5));
+        let (mut buffer, mut bits) = (0u32, 0);
+        for byte in bytes {
+            buffer = (buffer << 8) | u32::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                token.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+            }
+        }
+        if bits > 0 {
+            token.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+        }
+        token
+    }
+
+    /// Decodes a token from `to_short`. Case and surrounding whitespace are
+    /// ignored; the CRC is checked as in `from_bytes`.
+    pub fn from_short(token: &str) -> Result<Self, ProofError> {
+        let mut bytes = Vec::new();
+        let (mut buffer, mut bits) = (0u32, 0);
+        for c in token.trim().chars() {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|&digit| char::from(digit) == c.to_ascii_uppercase())
+                .ok_or_else(|| ProofError::Malformed(format!("'{}' is not a base32 digit", c)))?;
+            buffer = (buffer << 5) | value as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Appends `value` as an LEB128 varint (7 bits per byte, low bits first).
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Reads one LEB128 varint from the front of `input`, advancing it.
+/// Returns `None` if the input ends mid-varint or the value overflows `u64`.
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input.split_first()?;
+        *input = rest;
+        let bits = u64::from(byte & 0x7F);
+        if shift == 63 && bits > 1 {
+            return None;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// CRC-32 (IEEE 802.3, as used by zip and PNG), bit by bit: artifacts are tiny.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(width: usize, height: usize, steps: usize) -> ProofParams {
+        ProofParams { width, height, steps, algo: HashAlgo::Sha256, iterations: 1, warmup: 0 }
+    }
+
+    fn lookup(username: &str) -> Option<String> {
+        match username {
+            "alice" => Some("secret".to_string()),
+            "bob" => Some("hunter2".to_string()),
+            _ => None,
+        }
+    }
+
+    fn run_stream(input: &str, limits: VerifyLimits) -> String {
+        let mut output = Vec::new();
+        verify_stream_with_limits(input.as_bytes(), &mut output, limits, lookup).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn stream_reports_every_line() {
+        let p = params(16, 16, 10);
+        let good = format_proof(&p, &p.prove("alice", "secret"));
+        let wrong = format_proof(&p, &p.prove("bob", "wrong"));
+        let short = ProofArtifact::from_proof(&format_proof(&p, &p.prove("bob", "hunter2"))).unwrap().to_short();
+        let input = format!("alice {}\n\nbob {}\nbob {}\nmallory {}\nnonsense\n", good, wrong, short, good);
+
+        assert_eq!(
+            run_stream(&input, VerifyLimits::default()),
+            "VALID alice\nINVALID bob\nVALID bob\nERROR 5: unknown user 'mallory'\n\
+             ERROR 6: expected '<username> <proof>'\n"
+        );
+    }
+
+    #[test]
+    fn artifacts_from_before_the_v2_seed_are_unsupported() {
+        for (iterations, warmup) in [(1, 0), (3, 0), (1, 16)] {
+            let p = ProofParams { iterations, warmup, ..params(8, 8, 4) };
+            let mut bytes = ProofArtifact::from_proof(&format_proof(&p, &"ab".repeat(32))).unwrap().to_bytes();
+            assert_eq!(ProofArtifact::from_bytes(&bytes).unwrap().params, p);
+
+            for old in 1..=4 {
+                bytes[0] = old;
+                let body = bytes.len() - 4;
+                let crc = crc32(&bytes[..body]).to_le_bytes();
+                bytes[body..].copy_from_slice(&crc);
+                assert_eq!(ProofArtifact::from_bytes(&bytes), Err(ProofError::UnsupportedVersion(old.to_string())));
+            }
+        }
+    }
+
+    #[test]
+    fn stream_rejects_submissions_over_the_limits() {
+        let limits = VerifyLimits { max_cells: 256, max_steps: 10, max_iterations: 4 };
+        let hash = "0".repeat(64);
+        let input = format!(
+            "alice v2:16x16:10:{hash}\nalice v2:17x16:10:{hash}\nalice v2:16x16:11:{hash}\n\
+             alice v2:16x16:10:i5:{hash}\nalice v2:4294967296x4294967296:1:{hash}\n"
+        );
+
+        assert_eq!(
+            run_stream(&input, limits),
+            "INVALID alice\nERROR 2: grid of 272 cells exceeds the limit of 256\n\
+             ERROR 3: 11 steps exceeds the limit of 10\nERROR 4: 5 seed iterations exceeds the limit of 4\n\
+             ERROR 5: Grid 4294967296x4294967296 exceeds the maximum cell count\n"
+        );
+    }
+
+    #[test]
+    fn proof_strings_round_trip() {
+        let cases = [
+            params(16, 12, 10),
+            ProofParams { iterations: 3, ..params(16, 12, 10) },
+            ProofParams { warmup: 16, algo: HashAlgo::Sha512, ..params(8, 8, 0) },
+            ProofParams { iterations: 2, warmup: 1, algo: HashAlgo::Sha256Trunc(16), ..params(5, 9, 3) },
+        ];
+        for p in cases {
+            let hash = p.prove("alice", "secret");
+            let proof = format_proof(&p, &hash);
+            assert_eq!(parse_proof(&proof), Ok((p, hash)));
+            assert_eq!(verify_proof("alice", "secret", &proof), Ok(true));
+            assert_eq!(verify_proof("alice", "wrong", &proof), Ok(false));
+        }
+        assert_eq!(format_proof(&params(16, 12, 10), "ab"), "v2:16x12:10:ab");
+    }
+
+    #[test]
+    fn malformed_proof_strings_are_rejected() {
+        let hash = "0".repeat(64);
+        let malformed = [
+            format!("v2:16x12:{hash}"),
+            format!("v2:16-12:10:{hash}"),
+            format!("v2:16x12:ten:{hash}"),
+            format!("v2:16x12:10:i0:{hash}"),
+            format!("v2:16x12:10:md5:{hash}"),
+            format!("v2:16x12:10:sha256:i2:{hash}"),
+            "v2:16x12:10:abc".to_string(),
+            format!("v2:16x12:10:{}", hash.to_uppercase().replace('0', "A")),
+        ];
+        for proof in malformed {
+            assert!(matches!(parse_proof(&proof), Err(ProofError::Malformed(_))), "accepted {}", proof);
+        }
+        assert_eq!(parse_proof(&format!("v1:16x12:10:{hash}")), Err(ProofError::UnsupportedVersion("v1".into())));
+        assert_eq!(parse_proof(""), Err(ProofError::UnsupportedVersion(String::new())));
+    }
+
+    #[test]
+    fn artifacts_round_trip_and_catch_corruption() {
+        let p = params(500, 500, 1000);
+        let proof = format_proof(&p, &"3c".repeat(32));
+        let artifact = ProofArtifact::from_proof(&proof).unwrap();
+        let bytes = artifact.to_bytes();
+        assert_eq!(bytes.len(), 44);
+        assert_eq!(ProofArtifact::from_bytes(&bytes), Ok(artifact.clone()));
+        assert_eq!(artifact.to_proof(), proof);
+
+        for i in 0..bytes.len() {
+            let mut corrupt = bytes.clone();
+            corrupt[i] ^= 0x10;
+            assert_eq!(ProofArtifact::from_bytes(&corrupt), Err(ProofError::ChecksumMismatch), "byte {}", i);
+        }
+        assert!(matches!(ProofArtifact::from_bytes(&bytes[..3]), Err(ProofError::Malformed(_))));
+    }
+
+    #[test]
+    fn streaming_verifier_rejects_a_single_flipped_bit() {
+        for p in [params(40, 30, 25), ProofParams { algo: HashAlgo::Sha512, iterations: 2, ..params(9, 7, 3) }] {
+            let hash = p.prove("alice", "secret");
+            assert_eq!(verify_streaming("alice", "secret", &format_proof(&p, &hash)), Ok(true));
+
+            let mut digest = hex::decode(&hash).unwrap();
+            for bit in [0, 7, digest.len() * 8 - 1] {
+                digest[bit / 8] ^= 1 << (bit % 8);
+                let flipped = format_proof(&p, &hex::encode(&digest));
+                assert_eq!(verify_streaming("alice", "secret", &flipped), Ok(false));
+                digest[bit / 8] ^= 1 << (bit % 8);
+            }
+            assert_eq!(verify_streaming("alice", "wrong", &format_proof(&p, &hash)), Ok(false));
+        }
+        assert!(verify_streaming("alice", "secret", "v2:40x30:25:abc").is_err());
+
+        let p = ProofParams { algo: HashAlgo::Sha256Trunc(16), ..params(20, 12, 9) };
+        let proof = format_proof(&p, &p.prove_with_context("alice", "secret", b"challenge"));
+        assert_eq!(verify_streaming_with_context("alice", "secret", &proof, b"challenge"), Ok(true));
+        assert_eq!(verify_streaming_with_context("alice", "secret", &proof, b"other"), Ok(false));
+        assert_eq!(verify_streaming("alice", "secret", &proof), Ok(false));
+        let huge = format!("v2:{}x2:1:{}", isize::MAX, "0".repeat(64));
+        assert!(matches!(verify_streaming("alice", "secret", &huge), Err(ProofError::OverLimit(_))));
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd") && !constant_time_eq(b"ab", b"abc"));
+    }
+
+    #[test]
+    fn short_tokens_round_trip() {
+        let p = params(500, 500, 1000);
+        let proof = format_proof(&p, &"3c".repeat(32));
+        let artifact = ProofArtifact::from_proof(&proof).unwrap();
+        let token = artifact.to_short();
+        assert!(token.bytes().all(|b| BASE32_ALPHABET.contains(&b)));
+        assert_eq!(ProofArtifact::from_short(&token), Ok(artifact.clone()));
+        assert_eq!(ProofArtifact::from_short(&format!("  {}\n", token.to_ascii_lowercase())), Ok(artifact.clone()));
+        assert_eq!(ProofArtifact::from_short(&token).unwrap().to_proof(), proof);
+        assert!(matches!(ProofArtifact::from_short("AB1"), Err(ProofError::Malformed(_))));
+    }
+
+    #[test]
+    fn embedded_seeds_verify_like_the_credentials() {
+        let p = params(24, 16, 30);
+        let proof = format_proof(&p, &p.prove("alice", "secret"));
+        let artifact = ProofArtifact::from_proof(&proof).unwrap().with_seed(p.seed_grid("alice", "secret"));
+        let decoded = ProofArtifact::from_bytes(&artifact.to_bytes()).unwrap();
+        assert_eq!(decoded, artifact);
+        assert_eq!(decoded.verify_seed(), Ok(true));
+        assert_eq!(verify_artifact("alice", "secret", &decoded), Ok(true));
+
+        let wrong = decoded.clone().with_seed(p.seed_grid("alice", "wrong"));
+        assert_eq!(wrong.verify_seed(), Ok(false));
+        assert_eq!(verify_artifact("alice", "wrong", &decoded), Ok(false));
+        assert_eq!(ProofArtifact::from_proof(&proof).unwrap().verify_seed(), Err(ProofError::MissingSeed));
+    }
+
+    #[test]
+    fn artifacts_verify_against_their_own_parameters() {
+        let p = ProofParams { iterations: 3, warmup: 5, ..params(20, 12, 15) };
+        let proof = format_proof(&p, &p.prove("alice", "secret"));
+        let artifact = ProofArtifact::from_bytes(&ProofArtifact::from_proof(&proof).unwrap().to_bytes()).unwrap();
+        assert_eq!(verify_artifact("alice", "secret", &artifact), Ok(true));
+        assert_eq!(verify_artifact("alice", "wrong", &artifact), Ok(false));
+
+        for tampered in [
+            ProofParams { steps: 16, ..p },
+            ProofParams { width: 21, ..p },
+            ProofParams { iterations: 1, ..p },
+            ProofParams { warmup: 0, ..p },
+        ] {
+            let forged = ProofArtifact { params: tampered, ..artifact.clone() };
+            assert_eq!(verify_artifact("alice", "secret", &forged), Ok(false), "{:?}", tampered);
+        }
+    }
+
+    #[test]
+    fn oversized_proofs_are_errors_not_panics() {
+        let huge = format!("v2:{}x2:1:{}", isize::MAX, "ab".repeat(32));
+        assert!(matches!(verify_proof("alice", "secret", &huge), Err(ProofError::OverLimit(_))));
+        let artifact = ProofArtifact::from_proof(&huge).unwrap();
+        assert!(matches!(verify_artifact("alice", "secret", &artifact), Err(ProofError::OverLimit(_))));
+
+        let p = params(20, 12, 15);
+        let artifact = ProofArtifact::from_proof(&format_proof(&p, &p.prove("alice", "secret"))).unwrap();
+        let limits = VerifyLimits { max_cells: 240, max_steps: 15, max_iterations: 1 };
+        assert_eq!(verify_artifact_with_limits("alice", "secret", &artifact, &[], &limits), Ok(true));
+        for limits in [VerifyLimits { max_cells: 239, ..limits }, VerifyLimits { max_steps: 14, ..limits }] {
+            let verdict = verify_artifact_with_limits("alice", "secret", &artifact, &[], &limits);
+            assert!(matches!(verdict, Err(ProofError::OverLimit(_))), "{:?}", limits);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use sha2::{Digest, Sha256};
+
+// --- Commit-Reveal ---
+//
+// For challenge flows where the client must fix its answer before the server
+// discloses anything further: the client sends `commit(proof_hash, nonce)`
+// first, and only later reveals `proof_hash` and `nonce`. The commitment is
+// SHA-256 over the length-prefixed proof hash followed by the nonce, so no
+// (proof, nonce) split can be shifted into another that hashes the same.
+//
+// The nonce must be fresh and unpredictable (e.g. 16+ random bytes); with a
+// guessable nonce the commitment leaks the proof hash to anyone who can
+// enumerate candidates.
+
+/// Commits to `proof_hash` under `nonce`. Returns 64 lowercase hex chars.
+pub fn commit(proof_hash: &str, nonce: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update((proof_hash.len() as u64).to_le_bytes());
+    hasher.update(proof_hash.as_bytes());
+    hasher.update(nonce);
+    hex::encode(hasher.finalize())
+}
+
+/// Checks that a revealed `proof_hash` and `nonce` open `commitment`.
+///
+/// `commitment` is compared case-insensitively; `proof_hash` is taken as-is,
+/// since it is the exact string that was committed to.
+pub fn verify_reveal(commitment: &str, proof_hash: &str, nonce: &[u8]) -> bool {
+    commit(proof_hash, nonce).eq_ignore_ascii_case(commitment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveals_open_only_their_commitment() {
+        let proof = "a1b2c3d4";
+        let nonce = b"0123456789abcdef";
+        let commitment = commit(proof, nonce);
+        assert_eq!(commitment.len(), 64);
+        assert!(verify_reveal(&commitment, proof, nonce));
+        assert!(verify_reveal(&commitment.to_uppercase(), proof, nonce));
+
+        assert!(!verify_reveal(&commitment, "a1b2c3d5", nonce));
+        assert!(!verify_reveal(&commitment, proof, b"0123456789abcdeg"));
+        assert!(!verify_reveal(&commit(proof, b"other nonce"), proof, nonce));
+        // Moving bytes between the hash and the nonce changes the commitment.
+        assert_ne!(commit("a1b2c3d4", b"e5"), commit("a1b2c3d4e5", b""));
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt;
+
+// --- Rules & Neighborhoods ---
+
+/// Offsets of the Moore neighborhood (8 surrounding cells).
+/// Kept at module scope so it isn't rebuilt for every cell.
+pub(crate) const MOORE_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1,  0),          (1,  0),
+    (-1,  1), (0,  1), (1,  1),
+];
+
+/// Offsets of the von Neumann neighborhood (4 orthogonal cells).
+pub(crate) const VON_NEUMANN_OFFSETS: [(isize, isize); 4] = [
+              (0, -1),
+    (-1,  0),          (1,  0),
+              (0,  1),
+];
+
+/// Which surrounding cells count as a cell's neighbors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The 8 surrounding cells (standard Life, the InkVerify default).
+    /// [X][X][X]
+    /// [X][ ][X]
+    /// [X][X][X]
+    #[default]
+    Moore,
+    /// The 4 orthogonal cells (N, E, S, W).
+    /// [ ][X][ ]
+    /// [X][ ][X]
+    /// [ ][X][ ]
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// The (dx, dy) offsets of every neighbor.
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Moore => &MOORE_OFFSETS,
+            Neighborhood::VonNeumann => &VON_NEUMANN_OFFSETS,
+        }
+    }
+}
+
+/// A neighborhood where each neighbor contributes its own integer weight to
+/// the neighbor sum, e.g. to make diagonal neighbors count double.
+///
+/// With every weight 1 this is the plain neighborhood:
+/// `WeightedNeighborhood::uniform(Neighborhood::Moore)` reproduces standard Life.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WeightedNeighborhood {
+    /// Each neighbor's (dx, dy) offset and weight.
+    pub offsets: Vec<((isize, isize), u8)>,
+}
+
+impl WeightedNeighborhood {
+    /// Every neighbor of `neighborhood` with weight 1.
+    pub fn uniform(neighborhood: Neighborhood) -> Self {
+        WeightedNeighborhood {
+            offsets: neighborhood.offsets().iter().map(|&offset| (offset, 1)).collect(),
+        }
+    }
+}
+
+/// A Life-like "B/S" rule: the neighbor counts that give birth to ink on an empty
+/// cell, and the counts that let existing ink survive.
+///
+/// Stored as two bitmasks (bit `n` set = "n neighbors qualifies"), so applying
+/// the rule is a shift and a mask instead of a branchy `match`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    /// B3/S23: Conway's Life, the rule every InkVerify proof uses by default.
+    /// - Survival: Ink stays if it has 2 or 3 neighbors
+    /// - Birth: Ink spreads to empty spots with 3 neighbors
+    /// - Death: Overcrowding (>3) or Loneliness (<2)
+    pub const INK: Rule = Rule {
+        birth: 1 << 3,
+        survival: (1 << 2) | (1 << 3),
+    };
+
+    /// Builds a rule from the birth and survival neighbor counts (each 0..=8).
+    /// Panics on a count above 8, since no neighborhood has more neighbors.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Rule {
+        let mask = |counts: &[u8]| {
+            counts.iter().fold(0u16, |mask, &n| {
+                assert!(n <= 8, "Neighbor count {} is out of range (0..=8)", n);
+                mask | (1 << n)
+            })
+        };
+        Rule {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+
+    /// Precomputes `apply` for every state/neighbor-count pair (see `RuleTable`).
+    pub const fn table(&self) -> RuleTable {
+        let mut next = [0u8; RULE_TABLE_LEN];
+        let mut neighbors = 0;
+        while neighbors < 9 {
+            next[neighbors] = ((self.birth >> neighbors) & 1) as u8;
+            next[9 + neighbors] = ((self.survival >> neighbors) & 1) as u8;
+            neighbors += 1;
+        }
+        RuleTable { next }
+    }
+
+    /// Returns the next state of a cell: 1 = Ink, 0 = Water.
+    /// Only 0 and 1 are meaningful states; anything else dies.
+    #[inline(always)]
+    pub fn apply(&self, state: u8, neighbors: u8) -> u8 {
+        let mask = match state {
+            0 => self.birth,
+            1 => self.survival,
+            _ => return 0,
+        };
+        ((mask >> neighbors) & 1) as u8
+    }
+}
+
+/// Entries in a `RuleTable`: states 0 and 1, times neighbor counts 0..=8.
+const RULE_TABLE_LEN: usize = 2 * 9;
+
+/// A `Rule` flattened into a lookup table for the hot tick loop: the next state
+/// of every (state, neighbors) pair at index `state * 9 + neighbors`, so
+/// deciding a cell is one array read instead of a branch on the state.
+///
+/// Gives exactly what `Rule::apply` gives for neighbor counts 0..=8, including
+/// 0 for any state other than 0 and 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RuleTable {
+    next: [u8; RULE_TABLE_LEN],
+}
+
+impl RuleTable {
+    /// The next state of a cell (see `Rule::apply`). `neighbors` must be at
+    /// most 8, as it always is for a real neighborhood.
+    #[inline(always)]
+    pub fn apply(&self, state: u8, neighbors: u8) -> u8 {
+        debug_assert!(neighbors <= 8, "Neighbor count {} is out of range (0..=8)", neighbors);
+        let index = usize::from(state) * 9 + usize::from(neighbors);
+        // States above 1 land past the table and die, as in `Rule::apply`.
+        self.next.get(index).copied().unwrap_or(0)
+    }
+}
+
+impl From<Rule> for RuleTable {
+    fn from(rule: Rule) -> Self {
+        rule.table()
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::INK
+    }
+}
+
+/// Formats in the usual "B3/S23" notation.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |mask: u16| -> String {
+            (0..=8u8)
+                .filter(|n| mask & (1 << n) != 0)
+                .map(|n| char::from(b'0' + n))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_agree_with_apply_for_every_pair() {
+        let everything: Vec<u8> = (0..=8).collect();
+        let rules = [Rule::INK, Rule::new(&[3, 6], &[2, 3]), Rule::new(&[], &[]), Rule::new(&everything, &everything)];
+        for rule in rules {
+            let table = RuleTable::from(rule);
+            for state in [0, 1, 2, 255] {
+                for neighbors in 0..=8 {
+                    let expected = rule.apply(state, neighbors);
+                    assert_eq!(table.apply(state, neighbors), expected, "{} {} {}", rule, state, neighbors);
+                }
+            }
+        }
+
+        // The original `match` of the Ink rule, cell for cell.
+        for (state, neighbors) in (0..=1).flat_map(|state| (0..=8).map(move |n| (state, n))) {
+            let expected = match (state, neighbors) {
+                (1, 2) | (1, 3) | (0, 3) => 1,
+                _ => 0,
+            };
+            assert_eq!(Rule::INK.table().apply(state, neighbors), expected);
+        }
+    }
+}
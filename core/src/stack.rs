@@ -0,0 +1,130 @@
+use crate::grid::{CellGrid, Grid, GridError};
+
+/// A fixed-size `W x H` grid stored inline (`[[u8; W]; H]`), with no heap
+/// allocation.
+///
+/// Meant for tiny simulations (test cases, embedded or `const` contexts) where
+/// a `Vec` is overkill. Wraps like `Grid` and plugs into the engine through
+/// `CellGrid`, so `engine::step_cells` ticks it exactly like a heap grid. Keep
+/// `W * H` small: the whole grid lives on the stack and is copied by value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StackGrid<const W: usize, const H: usize> {
+    rows: [[u8; W]; H],
+}
+
+impl<const W: usize, const H: usize> StackGrid<W, H> {
+    /// An all-empty grid. Usable in `const` items.
+    pub const fn new() -> Self {
+        StackGrid { rows: [[0; W]; H] }
+    }
+
+    /// A grid with the given rows (row `y` holds cells `(0..W, y)`).
+    pub const fn from_rows(rows: [[u8; W]; H]) -> Self {
+        StackGrid { rows }
+    }
+
+    /// The cells, row by row.
+    pub const fn rows(&self) -> &[[u8; W]; H] {
+        &self.rows
+    }
+
+    /// Reads (x, y), wrapping around the edges like `Grid::get`.
+    pub const fn get(&self, x: isize, y: isize) -> u8 {
+        let x = x.rem_euclid(W as isize) as usize;
+        let y = y.rem_euclid(H as isize) as usize;
+        self.rows[y][x]
+    }
+
+    /// Writes (x, y). Panics if out of bounds.
+    pub const fn set(&mut self, x: usize, y: usize, value: u8) {
+        self.rows[y][x] = value;
+    }
+
+    /// Copies a heap grid of exactly `W x H` cells.
+    pub fn from_grid(grid: &Grid<u8>) -> Result<Self, GridError> {
+        if grid.width() != W || grid.height() != H {
+            return Err(GridError::DimensionMismatch { expected: W * H, actual: grid.as_raw().len() });
+        }
+        let mut stack = StackGrid::new();
+        for (row, cells) in stack.rows.iter_mut().zip(grid.row_major().as_raw().chunks(W.max(1))) {
+            row.copy_from_slice(cells);
+        }
+        Ok(stack)
+    }
+
+    /// Copies into a heap grid of the same size.
+    pub fn to_grid(&self) -> Grid<u8> {
+        Grid::from_raw(W, H, self.rows.as_flattened().to_vec())
+    }
+}
+
+impl<const W: usize, const H: usize> Default for StackGrid<W, H> {
+    fn default() -> Self {
+        StackGrid::new()
+    }
+}
+
+impl<const W: usize, const H: usize> CellGrid for StackGrid<W, H> {
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn get(&self, x: isize, y: isize) -> u8 {
+        StackGrid::get(self, x, y)
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: u8) {
+        StackGrid::set(self, x, y, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::engine::{generate_grid_from_seed, step, step_cells};
+    use crate::rules::Rule;
+
+    /// A glider, usable without any allocation.
+    const GLIDER: StackGrid<8, 8> = {
+        let mut grid = StackGrid::new();
+        grid.set(1, 0, 1);
+        grid.set(2, 1, 1);
+        grid.set(0, 2, 1);
+        grid.set(1, 2, 1);
+        grid.set(2, 2, 1);
+        grid
+    };
+
+    #[test]
+    fn stack_grid_ticks_like_a_heap_grid() {
+        let seeded = StackGrid::<8, 8>::from_grid(&generate_grid_from_seed("alice", "secret", 8, 8)).unwrap();
+        for start in [GLIDER, seeded] {
+            let (mut stack, mut heap) = (start, start.to_grid());
+            for _ in 0..20 {
+                stack = step_cells(&stack, &Rule::INK);
+                heap = step(&heap, &Rule::INK);
+                assert_eq!(stack.to_grid(), heap);
+            }
+        }
+        // A glider moves one cell diagonally every 4 ticks and wraps back home.
+        let mut glider = GLIDER;
+        for _ in 0..32 {
+            glider = step_cells(&glider, &Rule::INK);
+        }
+        assert_eq!(glider, GLIDER);
+    }
+
+    #[test]
+    fn only_matching_sizes_convert() {
+        assert_eq!(StackGrid::<8, 8>::from_grid(&GLIDER.to_grid()), Ok(GLIDER));
+        assert_eq!(
+            StackGrid::<8, 8>::from_grid(&Grid::new(8, 7)),
+            Err(GridError::DimensionMismatch { expected: 64, actual: 56 })
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::grid::{cell_count, Grid};
+use std::io::{self, Read, Write};
+
+// --- Versioned On-Disk Grid Format (.inkg) ---
+//
+// Layout (all integers little-endian):
+//   magic:   b"INKG"
+//   version: u8 (= 1)
+//   width, height: u64
+//   storage: u8 (see `Storage`)
+//   data:
+//     raw    - `width * height` bytes, one per cell, row-major
+//     rle    - runs of (length: u32, value: u8) covering every cell in order
+//     packed - one bit per cell, row-major, least-significant bit first;
+//              only for binary (0/1) grids
+//
+// One container for checkpoints and interchange: readers check the magic and
+// version before trusting anything else, so new storage kinds or versions can
+// be added without old files being misread.
+
+/// File signature of a serialized grid.
+pub const GRID_MAGIC: [u8; 4] = *b"INKG";
+
+/// Version byte of the current grid format.
+const GRID_FORMAT_VERSION: u8 = 1;
+
+/// Most cells `read_from` accepts (2^30, 1 GiB at one byte per cell). RLE runs
+/// expand, so without a cap a few bytes of input could claim any grid the
+/// header names. Use `read_from_with_limit` to allow more (or fewer).
+pub const DEFAULT_MAX_READ_CELLS: usize = 1 << 30;
+
+/// How the cells are encoded after the header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Storage {
+    /// One byte per cell. Works for any cell values.
+    #[default]
+    Raw,
+    /// Run-length encoded. Small for mostly-empty or blocky grids.
+    Rle,
+    /// One bit per cell (8x smaller than raw). Binary grids only.
+    Packed,
+}
+
+impl Storage {
+    fn id(self) -> u8 {
+        match self {
+            Storage::Raw => 0,
+            Storage::Rle => 1,
+            Storage::Packed => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Storage::Raw),
+            1 => Some(Storage::Rle),
+            2 => Some(Storage::Packed),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+impl Grid<u8> {
+    /// Serializes the grid in the versioned `INKG` format (see the layout above).
+    /// Cells are always written row-major; a column-major grid reads back as
+    /// the same logical grid in row-major layout.
+    ///
+    /// Errors with `InvalidInput` if `storage` is `Packed` and a cell is neither
+    /// 0 nor 1, since packing would silently change it.
+    pub fn write_to<W: Write>(&self, mut out: W, storage: Storage) -> io::Result<()> {
+        let grid = self.row_major();
+        let cells = grid.as_raw();
+        if storage == Storage::Packed && cells.iter().any(|&cell| cell > 1) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "packed storage only holds binary (0/1) grids",
+            ));
+        }
+
+        out.write_all(&GRID_MAGIC)?;
+        out.write_all(&[GRID_FORMAT_VERSION])?;
+        out.write_all(&(self.width() as u64).to_le_bytes())?;
+        out.write_all(&(self.height() as u64).to_le_bytes())?;
+        out.write_all(&[storage.id()])?;
+
+        match storage {
+            Storage::Raw => out.write_all(cells)?,
+            Storage::Rle => {
+                let mut rest = cells;
+                while let Some(&value) = rest.first() {
+                    let run = rest.iter().take(u32::MAX as usize).take_while(|&&cell| cell == value).count();
+                    out.write_all(&(run as u32).to_le_bytes())?;
+                    out.write_all(&[value])?;
+                    rest = &rest[run..];
+                }
+            }
+            Storage::Packed => {
+                for chunk in cells.chunks(8) {
+                    let byte = chunk.iter().enumerate().fold(0u8, |byte, (bit, &cell)| byte | (cell << bit));
+                    out.write_all(&[byte])?;
+                }
+            }
+        }
+        out.flush()
+    }
+
+    /// Reads a grid written by `write_to`, of at most `DEFAULT_MAX_READ_CELLS`
+    /// cells (see `read_from_with_limit`).
+    pub fn read_from<R: Read>(input: R) -> io::Result<Grid<u8>> {
+        Self::read_from_with_limit(input, DEFAULT_MAX_READ_CELLS)
+    }
+
+    /// Reads a grid written by `write_to`, refusing grids of more than
+    /// `max_cells` cells.
+    ///
+    /// Errors with `InvalidData` on a foreign magic, an unknown version or
+    /// storage kind, dimensions over `max_cells`, or cell data that is truncated
+    /// or does not cover the grid exactly. Raw and packed data are buffered as
+    /// they are read; RLE runs expand, so an RLE grid can allocate up to
+    /// `max_cells` bytes from a few bytes of input. The limit is what bounds it.
+    pub fn read_from_with_limit<R: Read>(mut input: R, max_cells: usize) -> io::Result<Grid<u8>> {
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        if magic != GRID_MAGIC {
+            return Err(invalid_data("not an InkVerify grid (bad magic)"));
+        }
+
+        let version = read_u8(&mut input)?;
+        if version != GRID_FORMAT_VERSION {
+            return Err(invalid_data(format!("unsupported grid format version {}", version)));
+        }
+
+        let width = read_dimension(&mut input)?;
+        let height = read_dimension(&mut input)?;
+        let total = cell_count(width, height).map_err(|err| invalid_data(err.to_string()))?;
+        if total > max_cells {
+            return Err(invalid_data(format!("grid of {} cells exceeds the limit of {}", total, max_cells)));
+        }
+        let storage_id = read_u8(&mut input)?;
+        let storage = Storage::from_id(storage_id)
+            .ok_or_else(|| invalid_data(format!("unknown grid storage kind {}", storage_id)))?;
+
+        let cells = match storage {
+            Storage::Raw => read_bytes(&mut input, total)?,
+            Storage::Rle => {
+                let mut cells = Vec::new();
+                while cells.len() < total {
+                    let mut length = [0u8; 4];
+                    input.read_exact(&mut length)?;
+                    let run = u32::from_le_bytes(length) as usize;
+                    let value = read_u8(&mut input)?;
+                    if run == 0 || run > total - cells.len() {
+                        return Err(invalid_data("RLE runs do not cover the grid exactly"));
+                    }
+                    cells.resize(cells.len() + run, value);
+                }
+                cells
+            }
+            Storage::Packed => {
+                let bytes = read_bytes(&mut input, total.div_ceil(8))?;
+                (0..total).map(|i| (bytes[i / 8] >> (i % 8)) & 1).collect()
+            }
+        };
+
+        Ok(Grid::from_raw(width, height, cells))
+    }
+}
+
+fn read_u8<R: Read>(input: &mut R) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    input.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_dimension<R: Read>(input: &mut R) -> io::Result<usize> {
+    let mut bytes = [0u8; 8];
+    input.read_exact(&mut bytes)?;
+    usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| invalid_data("grid dimension does not fit in memory"))
+}
+
+/// Reads exactly `len` bytes without reserving them all in advance.
+fn read_bytes<R: Read>(input: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    input.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "grid data is truncated"));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(width: u64, height: u64, storage: Storage) -> Vec<u8> {
+        let mut bytes = GRID_MAGIC.to_vec();
+        bytes.push(GRID_FORMAT_VERSION);
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.push(storage.id());
+        bytes
+    }
+
+    #[test]
+    fn round_trips_every_storage() {
+        let grid = Grid::from_raw(5, 3, vec![0, 1, 1, 0, 0, 1, 1, 1, 0, 0, 0, 0, 1, 0, 1]);
+        for storage in [Storage::Raw, Storage::Rle, Storage::Packed] {
+            let mut bytes = Vec::new();
+            grid.write_to(&mut bytes, storage).unwrap();
+            assert_eq!(Grid::read_from(bytes.as_slice()).unwrap(), grid);
+        }
+    }
+
+    #[test]
+    fn rle_grid_over_the_limit_is_rejected() {
+        // One 5-byte run claiming 2^32 - 1 cells.
+        let mut bytes = header(u32::MAX as u64, 1, Storage::Rle);
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.push(1);
+        let err = Grid::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut small = Vec::new();
+        Grid::from_raw(4, 4, vec![1; 16]).write_to(&mut small, Storage::Rle).unwrap();
+        assert!(Grid::read_from_with_limit(small.as_slice(), 15).is_err());
+        assert!(Grid::read_from_with_limit(small.as_slice(), 16).is_ok());
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let mut bytes = header(4, 4, Storage::Raw);
+        bytes.extend_from_slice(&[0; 15]);
+        assert_eq!(Grid::read_from(bytes.as_slice()).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
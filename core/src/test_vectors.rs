@@ -0,0 +1,277 @@
+use crate::engine::{
+    generate_grid_with_endian, hash_grid, run_simulation, run_simulation_metered, HashAlgo, SeedEndian,
+};
+use crate::proof::{format_proof, verify_proof, ProofParams};
+use std::time::{Duration, Instant};
+
+/// A pinned input/output pair for cross-implementation compatibility.
+///
+/// Any reimplementation (e.g. in JavaScript) that seeds the grid from
+/// `(username, password)`, runs `steps` ticks on a `width x height` torus and
+/// hashes the final cells with SHA-256 must reproduce `expected_hash` exactly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestVector {
+    pub username: &'static str,
+    pub password: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub steps: usize,
+    /// Byte order of the seed word (`SeedEndian::Big` for canonical proofs).
+    pub endian: SeedEndian,
+    /// Lowercase hex SHA-256 of the final grid.
+    pub expected_hash: &'static str,
+}
+
+impl TestVector {
+    /// Runs this vector through the engine and returns the resulting hex hash.
+    pub fn compute_hash(&self) -> String {
+        let initial_grid = generate_grid_with_endian(self.username, self.password, self.width, self.height, self.endian);
+        let final_grid = run_simulation(initial_grid, self.steps);
+        hash_grid(&final_grid)
+    }
+
+    /// True if the engine still reproduces the pinned hash.
+    pub fn matches(&self) -> bool {
+        self.compute_hash() == self.expected_hash
+    }
+
+    /// True if the verifier accepts the pinned hash: `verify_proof` on the
+    /// vector's proof string for big-endian vectors, `matches` for
+    /// little-endian ones, which no proof string can describe.
+    pub fn verifies(&self) -> bool {
+        if self.endian != SeedEndian::Big {
+            return self.matches();
+        }
+        let params = ProofParams {
+            width: self.width,
+            height: self.height,
+            steps: self.steps,
+            algo: HashAlgo::Sha256,
+            iterations: 1,
+            warmup: 0,
+        };
+        verify_proof(self.username, self.password, &format_proof(&params, self.expected_hash)) == Ok(true)
+    }
+}
+
+// NOTE: These hashes are the protocol. If a change makes one of them fail,
+// that change breaks every stored proof and must be treated as a version bump.
+static KNOWN_VECTORS: [TestVector; 10] = [
+    // Zero steps: the hash of the freshly seeded grid.
+    TestVector {
+        username: "alice",
+        password: "correct horse battery staple",
+        width: 16,
+        height: 16,
+        steps: 0,
+        endian: SeedEndian::Big,
+        expected_hash: "6b177578e8d583ad3836c3133024518e607e541c3d72e0a5ef74da0bd5e98233",
+    },
+    // A single tick on the same seed.
+    TestVector {
+        username: "alice",
+        password: "correct horse battery staple",
+        width: 16,
+        height: 16,
+        steps: 1,
+        endian: SeedEndian::Big,
+        expected_hash: "7ffa55f2b2204f2d11d154ba42fc7a97e8442b8e68805c2ee530a0626e6c3d94",
+    },
+    TestVector {
+        username: "bob",
+        password: "hunter2",
+        width: 32,
+        height: 32,
+        steps: 50,
+        endian: SeedEndian::Big,
+        expected_hash: "3d216785c939dcb17bfbbc9bdc476bdc9b725c5493699da3979ec35a6ede5484",
+    },
+    // Non-square grid.
+    TestVector {
+        username: "carol",
+        password: "P@ssw0rd!",
+        width: 64,
+        height: 48,
+        steps: 100,
+        endian: SeedEndian::Big,
+        expected_hash: "954aad55a681d7d6c2c3bb5b722259ad56396d4034c047677d46d18eb97a75fb",
+    },
+    // Empty password.
+    TestVector {
+        username: "dave",
+        password: "",
+        width: 100,
+        height: 100,
+        steps: 200,
+        endian: SeedEndian::Big,
+        expected_hash: "cf824ecac2c21ae23e20d323c8dc24f2d29b40756030216b9dd728adde1e4a2e",
+    },
+    TestVector {
+        username: "erin",
+        password: "s3cr3t",
+        width: 200,
+        height: 200,
+        steps: 500,
+        endian: SeedEndian::Big,
+        expected_hash: "aba89f6994e5906be923f7cf68589b63f38a975ad952dfa003a2e6c204648dc6",
+    },
+    // Tiny grid where the wrap-around touches the same cells from both sides.
+    TestVector {
+        username: "frank",
+        password: "x",
+        width: 7,
+        height: 3,
+        steps: 25,
+        endian: SeedEndian::Big,
+        expected_hash: "c90232586b801f9558a76f2f963eccd831d9fe6775e4c8f1446b2331aa2132f2",
+    },
+    // More cells than one parallel seeding chunk (64K), so `parallel` builds
+    // are checked across a chunk boundary too.
+    TestVector {
+        username: "grace",
+        password: "across the chunk seam",
+        width: 320,
+        height: 240,
+        steps: 20,
+        endian: SeedEndian::Big,
+        expected_hash: "158910567255b0d3c5976a3b3c6549e087d863eb083ee50ef217daabfedf152c",
+    },
+    // Little-endian seed word: not a canonical proof, but pins the grid an
+    // implementation that reads the seed the other way round must produce.
+    // Same inputs as the first and third vectors above.
+    TestVector {
+        username: "alice",
+        password: "correct horse battery staple",
+        width: 16,
+        height: 16,
+        steps: 0,
+        endian: SeedEndian::Little,
+        expected_hash: "ac45ff94590d4b418049441cf507413de04e1c1c3ce6c1d3db16fe8d17c5c8b3",
+    },
+    TestVector {
+        username: "bob",
+        password: "hunter2",
+        width: 32,
+        height: 32,
+        steps: 50,
+        endian: SeedEndian::Little,
+        expected_hash: "db6c094e9180aa991c4c854beae384204390f2618714215c3f9bab1fd1583ab8",
+    },
+];
+
+/// Returns the pinned test vectors (small and medium grids, including a zero-steps case,
+/// one larger than a parallel seeding chunk, and two with a little-endian seed).
+pub fn known_vectors() -> &'static [TestVector] {
+    &KNOWN_VECTORS
+}
+
+// --- Vector Benchmark (Regression Tracking) ---
+
+/// Totals from `benchmark_vectors`: how long proving and verifying every
+/// pinned vector took, and whether each still gave its pinned hash.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VectorBenchmark {
+    /// Vectors run: `known_vectors().len() * rounds`.
+    pub runs: usize,
+    /// Runs whose proof or verification missed the pinned hash.
+    pub failures: usize,
+    /// Cell updates across every simulation, proving and verifying.
+    pub cells_updated: u64,
+    /// Wall-clock time of the proving half (seed, metered simulation, hash).
+    pub prove_time: Duration,
+    /// Wall-clock time of the verifying half (`TestVector::verifies`).
+    pub verify_time: Duration,
+}
+
+impl VectorBenchmark {
+    /// `prove_time + verify_time`.
+    pub fn total_time(&self) -> Duration {
+        self.prove_time + self.verify_time
+    }
+
+    /// `cells_updated / total_time`, or 0 if the run was too fast to measure.
+    pub fn cells_per_sec(&self) -> f64 {
+        let secs = self.total_time().as_secs_f64();
+        if secs > 0.0 { self.cells_updated as f64 / secs } else { 0.0 }
+    }
+}
+
+/// Proves, then verifies, every known vector `rounds` times, for comparing
+/// builds across engine changes. Each vector runs its simulation twice per
+/// round (once per half); a failure is counted, not fatal, so one broken
+/// vector still leaves a timing for the rest.
+pub fn benchmark_vectors(rounds: usize) -> VectorBenchmark {
+    let mut report = VectorBenchmark {
+        runs: 0,
+        failures: 0,
+        cells_updated: 0,
+        prove_time: Duration::ZERO,
+        verify_time: Duration::ZERO,
+    };
+
+    for _ in 0..rounds {
+        for vector in known_vectors() {
+            let start = Instant::now();
+            let TestVector { username, password, width, height, endian, .. } = *vector;
+            let grid = generate_grid_with_endian(username, password, width, height, endian);
+            let (final_grid, metrics) = run_simulation_metered(grid, vector.steps);
+            let proved = hash_grid(&final_grid) == vector.expected_hash;
+            report.prove_time += start.elapsed();
+
+            let start = Instant::now();
+            let verified = vector.verifies();
+            report.verify_time += start.elapsed();
+
+            report.runs += 1;
+            report.failures += usize::from(!(proved && verified));
+            report.cells_updated += 2 * metrics.cells_updated;
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_known_vector_reproduces_its_hash() {
+        for vector in known_vectors() {
+            assert_eq!(vector.compute_hash(), vector.expected_hash, "{:?}", vector);
+            assert!(vector.verifies(), "{:?}", vector);
+        }
+    }
+
+    #[test]
+    fn known_vectors_cover_the_documented_cases() {
+        let vectors = known_vectors();
+        assert!(vectors.iter().any(|vector| vector.steps == 0));
+        // Bigger than one 64 Ki-cell chunk of the parallel seeding fill.
+        assert!(vectors.iter().any(|vector| vector.width * vector.height > 64 * 1024));
+        assert_eq!(vectors.iter().filter(|vector| vector.endian == SeedEndian::Little).count(), 2);
+        assert!(vectors.iter().all(|vector| vector.expected_hash.len() == 64));
+    }
+
+    #[test]
+    fn both_seed_byte_orders_are_pinned() {
+        let seed = |endian| generate_grid_with_endian("alice", "correct horse battery staple", 16, 16, endian);
+        let (big, little) = (seed(SeedEndian::Big), seed(SeedEndian::Little));
+        assert_eq!(big, crate::engine::generate_grid_from_seed("alice", "correct horse battery staple", 16, 16));
+        assert_ne!(big, little);
+        assert_eq!(hash_grid(&big), "6b177578e8d583ad3836c3133024518e607e541c3d72e0a5ef74da0bd5e98233");
+        assert_eq!(hash_grid(&little), "ac45ff94590d4b418049441cf507413de04e1c1c3ce6c1d3db16fe8d17c5c8b3");
+    }
+
+    #[test]
+    fn benchmark_runs_every_vector_and_counts_its_work() {
+        let report = benchmark_vectors(2);
+        assert_eq!(report.runs, 2 * known_vectors().len());
+        assert_eq!(report.failures, 0);
+        assert!(report.cells_updated > 0);
+        // Each round re-runs every simulation twice: proving and verifying.
+        let per_round: u64 = known_vectors().iter().map(|v| (v.width * v.height * v.steps) as u64).sum();
+        assert_eq!(report.cells_updated, 2 * 2 * per_round);
+        assert_eq!(report.total_time(), report.prove_time + report.verify_time);
+        assert_eq!(benchmark_vectors(0).runs, 0);
+    }
+}
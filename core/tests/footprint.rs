@@ -0,0 +1,86 @@
+// Checks `estimate_footprint`, and the streaming verifier's two-buffer promise,
+// against what really gets allocated, measured with a counting global
+// allocator. Everything lives in one test so no other test allocates while a
+// measurement is running.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use inkverify_core::{
+    estimate_footprint, format_proof, verify_streaming_with_context, HashAlgo, ProofParams,
+    SimulationBuilder, StorageKind,
+};
+
+/// `System`, keeping track of the live bytes and their high-water mark.
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(live, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Peak bytes allocated by `work` on top of what was live before it.
+fn peak_during<R>(work: impl FnOnce() -> R) -> usize {
+    let before = LIVE.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    std::hint::black_box(work());
+    PEAK.load(Ordering::SeqCst) - before
+}
+
+/// Within 1% plus 4 KiB: the rest of a run (hashers, digests) is small next to
+/// the grids.
+fn assert_close(estimate: usize, measured: usize, what: &str) {
+    let slack = estimate / 100 + 4096;
+    assert!(
+        measured <= estimate + slack && measured + slack >= estimate,
+        "{}: estimated {}, measured {}",
+        what,
+        estimate,
+        measured
+    );
+}
+
+#[test]
+fn estimates_match_real_allocations() {
+    // Warm up the lazy one-time allocations (thread pool, stdout) first.
+    SimulationBuilder::default().prove("warm", "up", 64, 64, 2);
+
+    for (width, height, lanes, steps) in [(512, 512, 1, 3), (1000, 300, 1, 2), (700, 700, 4, 2)] {
+        let builder = SimulationBuilder::default().lanes(lanes);
+        let measured = peak_during(|| builder.prove("alice", "secret", width, height, steps));
+        let estimate = estimate_footprint(width, height, lanes, StorageKind::Bytes).unwrap();
+        assert_close(estimate, measured, &format!("{}x{} with {} lane(s)", width, height, lanes));
+    }
+
+    for (width, height, steps, algo) in [(800, 600, 6, HashAlgo::Sha256), (700, 700, 1, HashAlgo::Sha512)] {
+        let params = ProofParams { width, height, steps, algo, iterations: 1, warmup: 0 };
+        let proof = format_proof(&params, &params.prove_with_context("alice", "secret", b"session"));
+        let mut valid = false;
+        let measured = peak_during(|| {
+            valid = verify_streaming_with_context("alice", "secret", &proof, b"session").unwrap();
+        });
+        assert!(valid);
+        // The seed plus one scratch grid for the early ticks; the last tick
+        // writes only a row, so a one-step proof never holds a second grid.
+        let grids = if steps == 1 { 1 } else { 2 };
+        let limit = grids * width * height + width + 4096;
+        assert!(measured <= limit, "{}x{}x{}: measured {} > {}", width, height, steps, measured, limit);
+    }
+}
@@ -0,0 +1,88 @@
+// Browser-facing exports, driven through real JS values: the native tests in
+// lib.rs cover the same logic with Rust closures, these cover the JS side.
+// Run with `cargo test -p inkverify-core --target wasm32-unknown-unknown` and
+// `wasm-bindgen-test-runner` (from wasm-bindgen-cli) as the target's runner.
+
+#![cfg(target_arch = "wasm32")]
+
+use inkverify_core::{prove_work_detailed, prove_work_full, prove_work_with_callback};
+use js_sys::{Array, Function, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// A JS function that appends every argument it is called with to `seen`,
+/// then runs `body` (which sees the step as `step`).
+fn recorder(seen: &Array, body: &str) -> Function {
+    let function = Function::new_with_args("seen, step", &format!("seen.push(step); {}", body));
+    function.bind1(&JsValue::NULL, seen).unchecked_into()
+}
+
+fn steps(seen: &Array) -> Vec<f64> {
+    seen.iter().map(|value| value.as_f64().expect("steps are numbers")).collect()
+}
+
+/// `object[name]`, as JS would read it.
+fn get(object: &JsValue, name: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(name)).expect("readable property")
+}
+
+/// `object.name()`, as JS would call it.
+fn call(object: &JsValue, name: &str) -> JsValue {
+    let method: Function = get(object, name).dyn_into().expect("a method");
+    method.call0(object).expect("method call")
+}
+
+#[wasm_bindgen_test]
+fn progress_callbacks_are_monotonic_and_keep_the_proof() {
+    let seen = Array::new();
+    // A return value is ignored, whatever it is.
+    let hash = prove_work_with_callback("alice", "secret", 250, &recorder(&seen, "return 'ignored';")).unwrap();
+
+    assert_eq!(hash, inkverify_core::prove_work("alice", "secret", 250));
+    let steps = steps(&seen);
+    assert!(steps.len() > 1 && steps.len() <= 251, "{} callbacks", steps.len());
+    assert!(steps.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", steps);
+    assert_eq!(steps.last(), Some(&250.0));
+}
+
+#[wasm_bindgen_test]
+fn a_throwing_callback_aborts_the_proof() {
+    let seen = Array::new();
+    let callback = recorder(&seen, "if (step >= 50) { throw new Error('cancelled at ' + step); }");
+    let err = prove_work_with_callback("alice", "secret", 250, &callback).unwrap_err();
+
+    let message = String::from(err.dyn_into::<js_sys::Error>().expect("an Error").message());
+    let last = *steps(&seen).last().unwrap();
+    assert!(last >= 50.0 && last < 250.0, "{}", last);
+    assert_eq!(message, format!("cancelled at {}", last));
+}
+
+#[wasm_bindgen_test]
+fn detail_getters_are_readable_from_js() {
+    let expected = prove_work_detailed("alice", "secret", 40);
+    let details = JsValue::from(prove_work_detailed("alice", "secret", 40));
+
+    assert_eq!(get(&details, "hash").as_string(), Some(expected.hash.clone()));
+    assert_eq!(get(&details, "final_population").as_f64(), Some(expected.final_population as f64));
+    assert_eq!(get(&details, "steps_run").as_f64(), Some(40.0));
+    // getter_with_clone: reading twice gives the same value, not a moved-out one.
+    assert_eq!(get(&details, "hash").as_string(), Some(expected.hash));
+}
+
+#[wasm_bindgen_test]
+fn full_proof_methods_are_callable_from_js() {
+    let expected = prove_work_full("alice", "secret", 12, 9, 7).unwrap();
+    let result = JsValue::from(prove_work_full("alice", "secret", 12, 9, 7).unwrap());
+
+    assert_eq!(call(&result, "hash").as_string(), Some(expected.hash()));
+    assert_eq!(call(&result, "width").as_f64(), Some(12.0));
+    assert_eq!(call(&result, "height").as_f64(), Some(9.0));
+    assert_eq!(call(&result, "steps").as_f64(), Some(7.0));
+
+    let pixels: Uint8Array = call(&result, "pixels").dyn_into().expect("a Uint8Array");
+    assert_eq!(pixels.to_vec(), expected.pixels());
+    // A copy: scribbling on it leaves the next call's pixels alone.
+    pixels.fill(7, 0, pixels.length());
+    let again: Uint8Array = call(&result, "pixels").dyn_into().unwrap();
+    assert_eq!(again.to_vec(), expected.pixels());
+}